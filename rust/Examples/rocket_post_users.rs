@@ -11,35 +11,89 @@ Implement a POST /users API endpoint that:
 7. Calls the `save` method on a Database trait object if all validations pass.
 8. Returns the Record produced by `save` along with HTTP 201 on success.
 
+Every 400 carries a JSON body naming the offending field and what was wrong
+with it, e.g. `{"field": "age", "message": "age below minimum"}`, instead of
+a bare status code.
+
 Assumptions:
 - The Database trait exposes: fn save(&mut self, user: User) -> Record
-- User and Record structs are provided and use Rocket Serde.
+- Record uses Rocket Serde.
+- User is relaxed to `{ name: Option<String>, age: Option<serde_json::Value> }`
+  so this handler can tell "missing" apart from "wrong type" itself, rather
+  than letting Rocket's JSON guard reject the request before this code runs.
 */
 
-use crate::db::{Database, Record, User};
+use crate::db::{Database, Record};
 use rocket::http::Status;
-use rocket::serde::json::Json;
+use rocket::request::Request;
+use rocket::response::{self, Responder};
+use rocket::serde::json::{json, Json};
+use rocket::serde::{Deserialize, Serialize};
 use rocket::{post, State};
 use std::sync::Mutex;
 
+#[derive(Debug, Deserialize)]
+#[serde(crate = "rocket::serde")]
+pub struct User {
+    name: Option<String>,
+    age: Option<serde_json::Value>,
+}
+
+/// A single-field validation failure, rendered as a 400 with a JSON body
+/// naming the offending field.
+#[derive(Debug, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub struct ValidationError {
+    field: &'static str,
+    message: &'static str,
+}
+
+impl ValidationError {
+    fn new(field: &'static str, message: &'static str) -> Self {
+        ValidationError { field, message }
+    }
+}
+
+impl<'r> Responder<'r, 'static> for ValidationError {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        (
+            Status::BadRequest,
+            Json(json!({ "field": self.field, "message": self.message })),
+        )
+            .respond_to(req)
+    }
+}
+
 /// POST /users
 #[post("/users", format = "json", data = "<user>")]
 pub fn users(
     user: Json<User>,
     database: &State<Mutex<Box<dyn Database>>>,
-) -> Result<(Status, Json<Record>), Status> {
-    // Validate name length
-    if user.name.len() > 32 {
-        return Err(Status::BadRequest);
+) -> Result<(Status, Json<Record>), ValidationError> {
+    let user = user.into_inner();
+
+    let name = user
+        .name
+        .ok_or_else(|| ValidationError::new("name", "missing name"))?;
+    if name.len() > 32 {
+        return Err(ValidationError::new("name", "name too long"));
     }
 
-    // Validate minimum age
-    if user.age < 16 {
-        return Err(Status::BadRequest);
+    let age = user
+        .age
+        .ok_or_else(|| ValidationError::new("age", "missing age"))?;
+    let age = age
+        .as_i64()
+        .ok_or_else(|| ValidationError::new("age", "age is not a number"))?;
+    if age < 16 {
+        return Err(ValidationError::new("age", "age below minimum"));
     }
 
     // Persist user if validation passes
-    let record = database.lock().unwrap().save(user.into_inner());
+    let record = database
+        .lock()
+        .unwrap()
+        .save(crate::db::User { name, age });
 
     Ok((Status::Created, Json(record)))
 }
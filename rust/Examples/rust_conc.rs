@@ -3,23 +3,42 @@ use std::{
     error::Error,
     fs::File,
     io::Read,
-    path::PathBuf,
+    path::{Component, Path, PathBuf},
+    pin::Pin,
+    task::{Context, Poll},
     thread,
 };
 
+use bytes::Bytes;
+use futures::Stream;
 use tokio::fs;
+use tokio::io::AsyncWriteExt;
 use tokio::sync::oneshot;
 use zip::ZipArchive;
 
 type DynResult<T> = Result<T, Box<dyn Error + Send + Sync>>;
 
+// Largest chunk read from a blocking zip reader per poll, so a single huge
+// entry can't blow up RSS while we stream it out.
+const CHUNK_SIZE: usize = 65_536;
+
 #[tokio::main]
 async fn main() -> DynResult<()> {
     let args: Vec<String> = env::args().collect();
+
+    // `--all <input.zip> <output.dir>` streams every entry out, preserving
+    // paths. The original two-arg form still extracts just the first entry.
+    if args.len() == 4 && args[1] == "--all" {
+        let input = PathBuf::from(&args[2]);
+        let output_dir = PathBuf::from(&args[3]);
+        extract_all(input, output_dir).await?;
+        return Ok(());
+    }
+
     if args.len() != 3 {
         return Err(format!(
-            "Usage: {} <input.zip> <output.file>",
-            args[0]
+            "Usage: {} <input.zip> <output.file>\n       {} --all <input.zip> <output.dir>",
+            args[0], args[0]
         ).into());
     }
 
@@ -72,3 +91,142 @@ fn parse_zip(path: PathBuf) -> DynResult<Vec<u8>> {
 
     Ok(buffer)
 }
+
+/// Resolve `name` (an entry path as stored in the archive) under `root`,
+/// rejecting anything that normalizes outside of it (`..`, absolute paths).
+fn resolve_entry_path(root: &Path, name: &str) -> DynResult<PathBuf> {
+    let mut resolved = root.to_path_buf();
+    for component in Path::new(name).components() {
+        match component {
+            Component::Normal(part) => resolved.push(part),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => {
+                return Err(format!("Entry escapes output directory: {name}").into());
+            }
+        }
+    }
+    if !resolved.starts_with(root) {
+        return Err(format!("Entry escapes output directory: {name}").into());
+    }
+    Ok(resolved)
+}
+
+/// Per-entry streaming state. The synchronous `zip` reader lives entirely on
+/// a `spawn_blocking` worker that owns a running offset/counter and pushes
+/// each chunk through a bounded channel; the channel's capacity is what
+/// backpressures the blocking thread when the async writer falls behind.
+struct ZipEntryStream {
+    chunks: tokio::sync::mpsc::Receiver<DynResult<Bytes>>,
+    _worker: tokio::task::JoinHandle<()>,
+}
+
+impl ZipEntryStream {
+    fn spawn(mut archive: ZipArchive<File>, index: usize) -> Self {
+        // Capacity 1: at most one chunk sits ahead of the consumer.
+        let (tx, rx) = tokio::sync::mpsc::channel(1);
+
+        let worker = tokio::task::spawn_blocking(move || {
+            let mut entry = match archive.by_index(index) {
+                Ok(entry) => entry,
+                Err(e) => {
+                    let _ = tx.blocking_send(Err(format!("Failed to open entry {index}: {e}").into()));
+                    return;
+                }
+            };
+
+            let total_len = entry.size();
+            let mut offset = 0u64;
+            while offset < total_len {
+                let want = std::cmp::min(total_len - offset, CHUNK_SIZE as u64) as usize;
+                let mut buf = vec![0u8; want];
+                let mut filled = 0;
+                let mut read_err = None;
+                while filled < want {
+                    match entry.read(&mut buf[filled..]) {
+                        Ok(0) => {
+                            read_err = Some("Unexpected EOF before entry was fully read".into());
+                            break;
+                        }
+                        Ok(n) => filled += n,
+                        Err(e) => {
+                            read_err = Some(format!("Failed to read entry {index}: {e}").into());
+                            break;
+                        }
+                    }
+                }
+
+                if let Some(err) = read_err {
+                    let _ = tx.blocking_send(Err(err));
+                    return;
+                }
+
+                offset += filled as u64;
+                if tx.blocking_send(Ok(Bytes::from(buf))).is_err() {
+                    return; // Consumer dropped the stream.
+                }
+            }
+        });
+
+        ZipEntryStream {
+            chunks: rx,
+            _worker: worker,
+        }
+    }
+}
+
+impl Stream for ZipEntryStream {
+    type Item = DynResult<Bytes>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut().chunks.poll_recv(cx)
+    }
+}
+
+/// Extract every entry of `path`, preserving relative paths under `output_dir`,
+/// streaming each entry in bounded chunks instead of buffering whole files.
+async fn extract_all(path: PathBuf, output_dir: PathBuf) -> DynResult<()> {
+    fs::create_dir_all(&output_dir).await?;
+
+    let names: Vec<(String, bool)> = {
+        let file = File::open(&path)
+            .map_err(|e| format!("Failed to open zip file {}: {e}", path.display()))?;
+        let mut archive = ZipArchive::new(file)
+            .map_err(|e| format!("Invalid ZIP archive: {e}"))?;
+        (0..archive.len())
+            .map(|i| {
+                let entry = archive
+                    .by_index(i)
+                    .map_err(|e| format!("Failed to read entry {i}: {e}"))?;
+                Ok((entry.name().to_string(), entry.is_dir()))
+            })
+            .collect::<DynResult<Vec<_>>>()?
+    };
+
+    for (index, (name, is_dir)) in names.into_iter().enumerate() {
+        let dest = resolve_entry_path(&output_dir, &name)?;
+
+        if is_dir {
+            fs::create_dir_all(dest).await?;
+            continue;
+        }
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+
+        let file = File::open(&path)
+            .map_err(|e| format!("Failed to reopen zip file {}: {e}", path.display()))?;
+        let archive = ZipArchive::new(file)
+            .map_err(|e| format!("Invalid ZIP archive: {e}"))?;
+        let mut stream = ZipEntryStream::spawn(archive, index);
+
+        let mut out = fs::File::create(&dest).await?;
+        while let Some(chunk) = futures::StreamExt::next(&mut stream).await {
+            out.write_all(&chunk?).await?;
+        }
+        out.flush().await?;
+
+        println!("Extracted {} -> {}", name, dest.display());
+    }
+
+    Ok(())
+}
@@ -256,23 +256,41 @@ impl AsyncTrait for MyType {
 use std::future::Future;
 use std::pin::Pin;
 use std::task::{Context, Poll, Wake, Waker};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::collections::VecDeque;
+use std::thread;
+use std::time::Duration;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
 
 struct Task {
     future: Pin<Box<dyn Future<Output = ()>>>,
+    // Set by this task's own waker when something wakes it; cleared right
+    // before each poll so we can tell "woke during this tick" from "still
+    // parked" afterwards.
+    woken: Arc<AtomicBool>,
 }
 
 impl Task {
     fn new(future: impl Future<Output = ()> + 'static) -> Self {
         Task {
             future: Box::pin(future),
+            woken: Arc::new(AtomicBool::new(true)),
         }
     }
-    
+
     fn poll(&mut self, context: &mut Context) -> Poll<()> {
+        self.woken.store(false, Ordering::Release);
         self.future.as_mut().poll(context)
     }
+
+    fn waker(&self) -> Waker {
+        Arc::new(TaskWaker {
+            woken: self.woken.clone(),
+        })
+        .into()
+    }
 }
 
 // Waker implementation
@@ -284,26 +302,70 @@ impl Wake for DummyWaker {
     }
 }
 
+// Per-task waker for the throttling executor: flips a flag instead of
+// notifying anything, since the executor only ever checks it between ticks.
+struct TaskWaker {
+    woken: Arc<AtomicBool>,
+}
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.woken.store(true, Ordering::Release);
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.woken.store(true, Ordering::Release);
+    }
+}
+
 // Simple single-threaded executor
 struct Executor {
     tasks: VecDeque<Task>,
+    throttle: Option<ThrottleConfig>,
+}
+
+// Budget and pacing for the low-CPU mode: poll at most `max_polls_per_tick`
+// ready tasks per tick, then sleep `tick_duration` if nothing became ready.
+struct ThrottleConfig {
+    max_polls_per_tick: usize,
+    tick_duration: Duration,
 }
 
 impl Executor {
     fn new() -> Self {
         Executor {
             tasks: VecDeque::new(),
+            throttle: None,
         }
     }
-    
+
+    // Rate-limited mode, suitable for workloads like the Pokemon fetcher
+    // where busy-spinning on `Pending` would waste CPU for no benefit.
+    fn throttled(max_polls_per_tick: usize, tick_duration: Duration) -> Self {
+        Executor {
+            tasks: VecDeque::new(),
+            throttle: Some(ThrottleConfig {
+                max_polls_per_tick,
+                tick_duration,
+            }),
+        }
+    }
+
     fn spawn(&mut self, future: impl Future<Output = ()> + 'static) {
         self.tasks.push_back(Task::new(future));
     }
-    
+
     fn run(&mut self) {
+        match self.throttle.take() {
+            None => self.run_spinning(),
+            Some(config) => self.run_throttled(config),
+        }
+    }
+
+    fn run_spinning(&mut self) {
         let waker = Arc::new(DummyWaker).into();
         let mut context = Context::from_waker(&waker);
-        
+
         while let Some(mut task) = self.tasks.pop_front() {
             match task.poll(&mut context) {
                 Poll::Ready(()) => {
@@ -316,6 +378,125 @@ impl Executor {
             }
         }
     }
+
+    fn run_throttled(&mut self, config: ThrottleConfig) {
+        let mut parked: Vec<Task> = Vec::new();
+
+        while !self.tasks.is_empty() || !parked.is_empty() {
+            // Anything a waker flipped while parked gets another chance
+            // this tick, same as a freshly-spawned or rescheduled task.
+            let (woken, still_parked): (Vec<_>, Vec<_>) = parked
+                .into_iter()
+                .partition(|task| task.woken.load(Ordering::Acquire));
+            parked = still_parked;
+            self.tasks.extend(woken);
+
+            let mut ready_next_tick = VecDeque::new();
+            let mut polled = 0;
+
+            while polled < config.max_polls_per_tick {
+                let Some(mut task) = self.tasks.pop_front() else {
+                    break;
+                };
+                polled += 1;
+
+                let waker = task.waker();
+                let mut context = Context::from_waker(&waker);
+                match task.poll(&mut context) {
+                    Poll::Ready(()) => {} // Task completed.
+                    Poll::Pending => {
+                        if task.woken.load(Ordering::Acquire) {
+                            ready_next_tick.push_back(task);
+                        } else {
+                            parked.push(task);
+                        }
+                    }
+                }
+            }
+
+            // Budget-exhausted tasks are still ready; don't make them wait
+            // out a full parked cycle.
+            ready_next_tick.extend(self.tasks.drain(..));
+
+            if ready_next_tick.is_empty() && !parked.is_empty() {
+                thread::sleep(config.tick_duration);
+            }
+
+            self.tasks = ready_next_tick;
+        }
+    }
+}
+
+// Local task set: like `Executor`, but for futures that capture `Rc`/
+// `RefCell` and so aren't `Send`. Since every task here runs on the thread
+// that owns the `RefCell`, never crossing threads, driving them without a
+// `Send` bound is sound.
+thread_local! {
+    static LOCAL_TASKS: RefCell<VecDeque<Pin<Box<dyn Future<Output = ()>>>>> =
+        RefCell::new(VecDeque::new());
+    static RUN_UNTIL_ACTIVE: Cell<bool> = Cell::new(false);
+}
+
+// A namespace for the local-task-queue functions below, not an instantiated
+// handle - the queue itself lives in `LOCAL_TASKS`, one per thread.
+pub struct LocalTaskSet;
+
+impl LocalTaskSet {
+    /// Queues `fut` to run alongside whatever `run_until` is currently
+    /// driving this thread's local tasks.
+    ///
+    /// Panics if called from outside an active `run_until` - there is no
+    /// queue to spawn into otherwise.
+    pub fn spawn_local(fut: impl Future<Output = ()> + 'static) {
+        if !RUN_UNTIL_ACTIVE.with(Cell::get) {
+            panic!("spawn_local called outside an active LocalTaskSet::run_until");
+        }
+        LOCAL_TASKS.with(|tasks| {
+            tasks.borrow_mut().push_back(Box::pin(fut));
+        });
+    }
+
+    /// Drives both the local task queue and `main_fut` to completion on the
+    /// current thread, returning `main_fut`'s output. Once `main_fut`
+    /// resolves, the remaining local tasks keep being polled until the queue
+    /// is empty, and only then does `run_until` return.
+    pub fn run_until<F: Future>(main_fut: F) -> F::Output {
+        RUN_UNTIL_ACTIVE.with(|active| active.set(true));
+        let result = Self::drive(main_fut);
+        RUN_UNTIL_ACTIVE.with(|active| active.set(false));
+        result
+    }
+
+    fn drive<F: Future>(main_fut: F) -> F::Output {
+        let waker = Arc::new(DummyWaker).into();
+        let mut context = Context::from_waker(&waker);
+        let mut main_fut = Box::pin(main_fut);
+        let mut output = None;
+
+        loop {
+            if output.is_none() {
+                if let Poll::Ready(out) = main_fut.as_mut().poll(&mut context) {
+                    output = Some(out);
+                }
+            }
+
+            let next = LOCAL_TASKS.with(|tasks| tasks.borrow_mut().pop_front());
+            match next {
+                Some(mut task) => {
+                    if task.as_mut().poll(&mut context).is_pending() {
+                        LOCAL_TASKS.with(|tasks| tasks.borrow_mut().push_back(task));
+                    }
+                }
+                // Local queue drained: done if `main_fut` is too, otherwise
+                // keep polling it.
+                None => {
+                    if let Some(out) = output.take() {
+                        return out;
+                    }
+                }
+            }
+        }
+    }
 }
 
 // Usage
@@ -327,6 +508,22 @@ fn main() {
     let mut executor = Executor::new();
     executor.spawn(example_task());
     executor.run();
+
+    // Low-CPU mode: poll at most 4 ready tasks per tick, then sleep 10ms if
+    // nothing became ready instead of busy-spinning on `Pending`.
+    let mut throttled = Executor::throttled(4, Duration::from_millis(10));
+    throttled.spawn(example_task());
+    throttled.run();
+
+    // `!Send` futures sharing an `Rc<RefCell<_>>`, driven on this thread only.
+    let shared = Rc::new(RefCell::new(0));
+    let child = shared.clone();
+    LocalTaskSet::run_until(async move {
+        LocalTaskSet::spawn_local(async move {
+            *child.borrow_mut() += 1;
+        });
+        println!("Shared counter: {}", shared.borrow());
+    });
 }
 
 /// Reactor, notifies when IO is Ready
@@ -17,9 +17,114 @@ metrics = []
 tls = ["rustls"]
 
 
+/// Making it real: a registry of named, atomic counters behind a
+/// `OnceLock`. Looking a counter up takes a read lock on the table
+/// (shared across every thread that's already registered a counter),
+/// then a lock-free `fetch_add` on the `Arc<AtomicU64>` itself - the
+/// table is only ever write-locked the first time a given name is seen.
 #[cfg(feature = "metrics")]
+mod metrics {
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::{Arc, OnceLock, RwLock};
+
+    type Registry = RwLock<HashMap<&'static str, Arc<AtomicU64>>>;
+
+    fn registry() -> &'static Registry {
+        static REGISTRY: OnceLock<Registry> = OnceLock::new();
+        REGISTRY.get_or_init(|| RwLock::new(HashMap::new()))
+    }
+
+    fn counter(name: &'static str) -> Arc<AtomicU64> {
+        if let Some(counter) = registry().read().unwrap().get(name) {
+            return Arc::clone(counter);
+        }
+        Arc::clone(
+            registry()
+                .write()
+                .unwrap()
+                .entry(name)
+                .or_insert_with(|| Arc::new(AtomicU64::new(0))),
+        )
+    }
+
+    pub fn incr(name: &'static str) {
+        add(name, 1);
+    }
+
+    pub fn add(name: &'static str, n: u64) {
+        counter(name).fetch_add(n, Ordering::Relaxed);
+    }
+
+    pub fn snapshot() -> Vec<(&'static str, u64)> {
+        let mut values: Vec<(&'static str, u64)> = registry()
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(name, count)| (*name, count.load(Ordering::Relaxed)))
+            .collect();
+        values.sort_unstable_by_key(|(name, _)| *name);
+        values
+    }
+}
+
+/// With the feature off there's no registry, no locks, no atomics -
+/// every call below is a no-op the optimizer deletes entirely, which is
+/// what "zero runtime cost" actually means in practice.
+#[cfg(not(feature = "metrics"))]
+mod metrics {
+    pub fn incr(_name: &'static str) {}
+    pub fn add(_name: &'static str, _n: u64) {}
+    pub fn snapshot() -> Vec<(&'static str, u64)> {
+        Vec::new()
+    }
+}
+
+#[cfg(feature = "metrics")]
+fn record_metrics() {
+    metrics::incr("records_processed");
+}
+
+#[cfg(not(feature = "metrics"))]
 fn record_metrics() {}
 
+/// Proof that the totals come out right once every thread has joined.
+/// Each `incr` still takes the registry's shared read lock before its
+/// atomic add, so the hot path is not lock-free. This is the same kind of cross-thread
+/// bookkeeping the `BoundedQueue` producer/consumer demo relies on to
+/// assert `consumed == TOTAL_ITEMS`, just backed by `metrics::snapshot()`
+/// instead of an `AtomicUsize` counter.
+#[cfg(feature = "metrics")]
+fn metrics_example() {
+    use std::thread;
+
+    const THREADS: u64 = 8;
+    const INCREMENTS_PER_THREAD: u64 = 10_000;
+
+    let handles: Vec<_> = (0..THREADS)
+        .map(|_| {
+            thread::spawn(|| {
+                for _ in 0..INCREMENTS_PER_THREAD {
+                    metrics::incr("requests_handled");
+                }
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    let snapshot = metrics::snapshot();
+    assert_eq!(
+        snapshot
+            .iter()
+            .find(|(name, _)| *name == "requests_handled")
+            .map(|(_, count)| *count),
+        Some(THREADS * INCREMENTS_PER_THREAD)
+    );
+}
+
 
 //// Why use features
 /// - Optional dependencies
@@ -6,19 +6,25 @@
 
 use std::{
     borrow::Cow,
+    cell::RefCell,
+    collections::VecDeque,
     error::Error,
     fmt::{self, Display},
     rc::Rc,
     sync::{
         atomic::{AtomicUsize, Ordering},
-        mpsc,
-        Arc, Mutex, RwLock,
+        mpsc, Arc, Condvar, Mutex, RwLock,
     },
     thread,
-    time::Duration,
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
-use tokio::{sync::mpsc as async_mpsc, task};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    sync::mpsc as async_mpsc,
+    task,
+};
 
 // ============================================================
 // 0a - Send / Sync
@@ -243,6 +249,226 @@ async fn async_channel_example() {
     }
 }
 
+// ============================================================
+// 0j - Condvar (bounded producer/consumer queue)
+// ============================================================
+//
+// Condvar is the block-and-signal primitive underneath the channels above:
+// instead of polling, a thread parks on `wait` until another thread calls
+// `notify_one`/`notify_all`. Always re-check the predicate in a loop after
+// waking up - `wait` can return spuriously even when nothing changed.
+//
+
+struct BoundedQueue<T> {
+    queue: Mutex<VecDeque<T>>,
+    not_empty: Condvar,
+    not_full: Condvar,
+    capacity: usize,
+}
+
+impl<T> BoundedQueue<T> {
+    fn new(capacity: usize) -> Self {
+        BoundedQueue {
+            queue: Mutex::new(VecDeque::new()),
+            not_empty: Condvar::new(),
+            not_full: Condvar::new(),
+            capacity,
+        }
+    }
+
+    fn push(&self, item: T) {
+        let mut guard = self.queue.lock().unwrap();
+        while guard.len() == self.capacity {
+            guard = self.not_full.wait(guard).unwrap();
+        }
+        guard.push_back(item);
+        // Drop the guard before notifying so the woken thread doesn't
+        // immediately block again trying to re-acquire the mutex we're
+        // still holding.
+        drop(guard);
+        self.not_empty.notify_one();
+    }
+
+    fn pop(&self) -> T {
+        let mut guard = self.queue.lock().unwrap();
+        while guard.is_empty() {
+            guard = self.not_empty.wait(guard).unwrap();
+        }
+        let item = guard.pop_front().unwrap();
+        drop(guard);
+        self.not_full.notify_one();
+        item
+    }
+}
+
+fn bounded_queue_example() {
+    const CAPACITY: usize = 4;
+    const ITEMS_PER_PRODUCER: usize = 20;
+    const PRODUCERS: usize = 3;
+    const CONSUMERS: usize = 2;
+    const TOTAL_ITEMS: usize = PRODUCERS * ITEMS_PER_PRODUCER;
+
+    let queue = Arc::new(BoundedQueue::<usize>::new(CAPACITY));
+    let consumed = Arc::new(AtomicUsize::new(0));
+    // Consumers reserve a slot by decrementing this before popping, so two
+    // consumers racing near the end never both try to pop an item that was
+    // never produced.
+    let remaining = Arc::new(AtomicUsize::new(TOTAL_ITEMS));
+
+    let mut handles = vec![];
+
+    for p in 0..PRODUCERS {
+        let queue = Arc::clone(&queue);
+        handles.push(thread::spawn(move || {
+            for i in 0..ITEMS_PER_PRODUCER {
+                queue.push(p * ITEMS_PER_PRODUCER + i);
+            }
+        }));
+    }
+
+    for _ in 0..CONSUMERS {
+        let queue = Arc::clone(&queue);
+        let consumed = Arc::clone(&consumed);
+        let remaining = Arc::clone(&remaining);
+        handles.push(thread::spawn(move || {
+            while remaining
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |r| r.checked_sub(1))
+                .is_ok()
+            {
+                queue.pop();
+                consumed.fetch_add(1, Ordering::Relaxed);
+            }
+        }));
+    }
+
+    for h in handles {
+        h.join().unwrap();
+    }
+
+    assert_eq!(consumed.load(Ordering::Relaxed), TOTAL_ITEMS);
+    println!(
+        "Bounded queue: consumed {} of {} items, capacity {} never exceeded",
+        consumed.load(Ordering::Relaxed),
+        TOTAL_ITEMS,
+        CAPACITY
+    );
+}
+
+// ============================================================
+// 0k - Tokio TCP server with a once-per-second cached date header
+// ============================================================
+//
+// Re-formatting an RFC 1123 date string on every request is wasted work
+// when the wall clock only advances once a second: cache the rendered
+// bytes per thread and only re-format once the unix second has actually
+// changed, otherwise hand back the bytes already sitting in the buffer.
+//
+
+thread_local! {
+    // (rendered bytes, valid length, unix second the bytes were rendered for)
+    static DATE_CACHE: RefCell<([u8; 128], usize, u64)> =
+        RefCell::new(([0; 128], 0, u64::MAX));
+}
+
+const WEEKDAYS: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+// Days-since-epoch -> (year, month, day) via Howard Hinnant's
+// `civil_from_days` - integer-only so there's no floating-point rounding
+// and no calendar crate dependency for a once-a-second date string.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = if z >= 0 { z } else { z - 146_096 } / 146_097;
+    let doe = (z - era * 146_097) as u64;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let y = yoe as i64 + era * 400;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = if month <= 2 { y + 1 } else { y };
+    (year, month, day)
+}
+
+// Renders e.g. `"Thu, 01 Jan 1970 00:00:00 GMT"` into `buf`, returning the
+// number of bytes written.
+fn format_http_date(unix_secs: u64, buf: &mut [u8; 128]) -> usize {
+    let days = (unix_secs / 86_400) as i64;
+    let secs_of_day = unix_secs % 86_400;
+    let (year, month, day) = civil_from_days(days);
+    // Epoch day 0 (1970-01-01) was a Thursday, so `WEEKDAYS` starts there.
+    let weekday = WEEKDAYS[(days.rem_euclid(7)) as usize];
+    let (hour, minute, second) = (secs_of_day / 3600, (secs_of_day / 60) % 60, secs_of_day % 60);
+
+    let rendered = format!(
+        "{weekday}, {day:02} {} {year:04} {hour:02}:{minute:02}:{second:02} GMT",
+        MONTHS[(month - 1) as usize],
+    );
+    let bytes = rendered.as_bytes();
+    buf[..bytes.len()].copy_from_slice(bytes);
+    bytes.len()
+}
+
+fn cached_http_date() -> String {
+    let now_secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+
+    DATE_CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        let (buf, len, last_secs) = &mut *cache;
+        if *last_secs != now_secs {
+            *len = format_http_date(now_secs, buf);
+            *last_secs = now_secs;
+        }
+        String::from_utf8_lossy(&buf[..*len]).into_owned()
+    })
+}
+
+async fn handle_connection(mut socket: TcpStream) {
+    let mut request = [0u8; 512];
+    // This demo never inspects the request, just drains it so the client's
+    // `write_all` doesn't block on a full send buffer.
+    let _ = socket.read(&mut request).await;
+
+    let date = cached_http_date();
+    let body = "ok";
+    let response = format!(
+        "HTTP/1.1 200 OK\r\nDate: {date}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    let _ = socket.write_all(response.as_bytes()).await;
+    // Dropping `socket` here closes the connection, which is what lets the
+    // client's `read_to_end` below see EOF and return.
+}
+
+async fn tcp_server_example() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let local_addr = listener.local_addr().unwrap();
+
+    let server = task::spawn(async move {
+        for _ in 0..3 {
+            let (socket, _) = listener.accept().await.unwrap();
+            task::spawn(handle_connection(socket));
+        }
+    });
+
+    // Self-contained, like `sync_channel_example` above: a few clients
+    // connect to our own listener so the demo produces real traffic.
+    for _ in 0..3 {
+        let mut stream = TcpStream::connect(local_addr).await.unwrap();
+        stream.write_all(b"GET / HTTP/1.1\r\n\r\n").await.unwrap();
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).await.unwrap();
+        print!("{}", String::from_utf8_lossy(&response));
+    }
+
+    server.await.unwrap();
+}
+
 
 // ============================================================
 // Main
@@ -278,4 +504,10 @@ async fn main() {
     println!("\n--- Channels ---");
     sync_channel_example();
     async_channel_example().await;
+
+    println!("\n--- Condvar ---");
+    bounded_queue_example();
+
+    println!("\n--- Tokio TCP Server ---");
+    tcp_server_example().await;
 }
\ No newline at end of file
@@ -1,11 +1,17 @@
 // Child module
 // This is a submodule of network
 
+use std::time::Duration;
+
+use rand::Rng;
+
 // Public struct
 pub struct Client {
     id: u64,            // Private field
     pub name: String,   // Public field
-    timeout: u32,       // Private field
+    timeout: u32,       // Private field, per-request deadline in seconds
+    http: reqwest::Client,
+    max_retries: u32,
 }
 
 impl Client {
@@ -15,42 +21,128 @@ impl Client {
             id: rand::random(),  // Would need rand crate for this to work
             name: name.to_string(),
             timeout: 30,
+            http: reqwest::Client::new(),
+            max_retries: 3,
         }
     }
-    
-    // Method with &self
-    pub fn send(&self, message: &str) -> Result<(), String> {
-        if message.len() > 1000 {
-            Err("Message too long".to_string())
-        } else {
-            println!("Client {} sending: {}", self.name, message);
-            Ok(())
+
+    // Sends `message` as the body of a POST to `url`, retrying idempotent
+    // failures (connection errors, 5xx) with exponential backoff + jitter,
+    // bounded by `max_retries`. `set_timeout` governs the per-attempt deadline.
+    // Each attempt opens its own tracing span recording method, URL, status
+    // and elapsed time.
+    pub async fn send(&self, url: &str, message: &str) -> Result<String, ClientError> {
+        if !self.validate_message(message) {
+            return Err(ClientError::InvalidData);
         }
+
+        let deadline = Duration::from_secs(self.timeout as u64);
+        let mut last_error = ClientError::ConnectionFailed;
+
+        for attempt in 0..=self.max_retries {
+            let span = tracing::info_span!(
+                "client.send",
+                client = %self.name,
+                method = "POST",
+                url,
+                attempt,
+            );
+            let _enter = span.enter();
+            let started = std::time::Instant::now();
+
+            let attempt_result =
+                tokio::time::timeout(deadline, self.http.post(url).body(message.to_string()).send())
+                    .await;
+
+            let outcome = match attempt_result {
+                Err(_) => {
+                    tracing::warn!(elapsed = ?started.elapsed(), "request timed out");
+                    Err(ClientError::Timeout)
+                }
+                Ok(Err(e)) => {
+                    tracing::warn!(elapsed = ?started.elapsed(), error = %e, "transport error");
+                    Err(ClientError::Retryable(e.to_string()))
+                }
+                Ok(Ok(response)) if response.status().is_server_error() => {
+                    let status = response.status();
+                    tracing::warn!(elapsed = ?started.elapsed(), %status, "server error");
+                    Err(ClientError::Retryable(format!("HTTP {status}")))
+                }
+                Ok(Ok(response)) => {
+                    tracing::info!(elapsed = ?started.elapsed(), status = %response.status(), "request succeeded");
+                    Ok(response)
+                }
+            };
+
+            match outcome {
+                Ok(response) => {
+                    return response
+                        .text()
+                        .await
+                        .map_err(|e| ClientError::Retryable(e.to_string()));
+                }
+                Err(err) if attempt < self.max_retries => {
+                    last_error = err;
+                    tokio::time::sleep(Self::backoff_with_jitter(attempt)).await;
+                }
+                Err(err) => return Err(ClientError::Exhausted(Box::new(err))),
+            }
+        }
+
+        Err(ClientError::Exhausted(Box::new(last_error)))
     }
-    
-    // Method with &mut self
+
+    // base * 2^attempt, capped at 5s, plus up to 100ms of random jitter.
+    fn backoff_with_jitter(attempt: u32) -> Duration {
+        const BASE_MS: u64 = 100;
+        const MAX_MS: u64 = 5_000;
+        let exp = BASE_MS.saturating_mul(1u64 << attempt.min(16)).min(MAX_MS);
+        let jitter = rand::thread_rng().gen_range(0..=100);
+        Duration::from_millis(exp + jitter)
+    }
+
+    // Method with &mut self. Takes effect on the next call to `send`.
     pub fn set_timeout(&mut self, timeout: u32) {
         self.timeout = timeout;
     }
-    
+
     // Method that consumes self
     pub fn into_parts(self) -> (u64, String) {
         (self.id, self.name)
     }
-    
+
     // Private method
     fn validate_message(&self, message: &str) -> bool {
-        !message.is_empty() && message.len() <= self.timeout as usize
+        !message.is_empty() && message.len() <= 1000
     }
 }
 
 // Public enum
+#[derive(Debug)]
 pub enum ClientError {
     Timeout,
     ConnectionFailed,
     InvalidData,
+    // A single attempt failed but may succeed on retry
+    Retryable(String),
+    // The first attempt and all `max_retries` retries failed; carries the last error
+    Exhausted(Box<ClientError>),
+}
+
+impl std::fmt::Display for ClientError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ClientError::Timeout => write!(f, "request timed out"),
+            ClientError::ConnectionFailed => write!(f, "connection failed"),
+            ClientError::InvalidData => write!(f, "invalid data"),
+            ClientError::Retryable(msg) => write!(f, "retryable error: {msg}"),
+            ClientError::Exhausted(last) => write!(f, "retries exhausted, last error: {last}"),
+        }
+    }
 }
 
+impl std::error::Error for ClientError {}
+
 // Implement a trait for Client
 impl std::fmt::Display for Client {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -1,76 +1,488 @@
-use std::error::Error;
-use std::io::Write;
-use serde::{Deserialize, Serialize};
-use std::sync::{Arc, Mutex};
-use std::thread;
-use std::fs::File;
-
-#[derive(Debug, Deserialize, Serialize)]
-struct Pokemon {
-    name: String,
-    id: i32,
-    height: i32,
-}
-
-async fn get_pokemon(id: i32) -> Result<Pokemon, Box<dyn Error + Send + Sync>> {
-    let url = format!("https://pokeapi.co/api/v2/pokemon/{}", id);
-    let pokemon = reqwest::Client::new()
-        .get(url)
-        .send()
-        .await?
-        .json::<Pokemon>()
-        .await?;
-    Ok(pokemon)
-}
-
-async fn buscar_pokemons(ids: Vec<i32>) -> Vec<Pokemon> {
-    let tasks: Vec<_> = ids.into_iter()
-        .map(|id| tokio::spawn(async move { get_pokemon(id).await }))
-        .collect();
-    
-    let mut res = vec![];
-    for task in tasks {
-        if let Ok(Ok(pokemon)) = task.await {
-            res.push(pokemon);
-        }
-    }
-    res
-}
-
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn Error>> {
-    let args: Vec<String> = std::env::args().collect();
-    if args.len() < 2 {
-        return Err("Missing output path argument.".into());
-    }
-    
-    let chunks: Vec<Vec<i32>> = (1..=30)
-        .collect::<Vec<_>>()
-        .chunks(10)
-        .map(|c| c.to_vec())
-        .collect();
-    
-    let pokes = Arc::new(Mutex::new(Vec::new()));
-    let handles: Vec<_> = chunks.into_iter()
-        .map(|ids| {
-            let pokes = Arc::clone(&pokes);
-            thread::spawn(move || {
-                let runtime = tokio::runtime::Runtime::new().unwrap();
-                runtime.block_on(async {
-                    let res = buscar_pokemons(ids).await;
-                    pokes.lock().unwrap().extend(res);
-                });
-            })
-        })
-        .collect();
-    
-    for handle in handles {
-        handle.join().unwrap();
-    }
-    
-    let fin = Arc::try_unwrap(pokes).unwrap().into_inner().unwrap();
-    let json = serde_json::to_string_pretty(&fin)?;
-    File::create(&args[1])?.write_all(json.as_bytes())?;
-    
-    Ok(())
-}
\ No newline at end of file
+use std::error::Error;
+use std::future::Future;
+use std::io::Write;
+use serde::{Deserialize, Serialize};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::task::{Context, Poll, Waker};
+use std::fs::File;
+use std::time::Duration;
+use std::collections::HashMap;
+use rand::Rng;
+use tokio::sync::mpsc;
+use tokio::task::{AbortHandle, JoinError};
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Pokemon {
+    name: String,
+    id: i32,
+    height: i32,
+}
+
+// Monotonic ids for child-slot bookkeeping, so a dropped child can find and
+// remove its own entry in the parent's `children` by id, without comparing
+// `Weak` pointers.
+static NEXT_TOKEN_ID: AtomicUsize = AtomicUsize::new(1);
+
+// Shared node in a cancellation tree. Cancelling a node wakes its waiters
+// and propagates down to every still-alive child.
+struct TokenState {
+    cancelled: AtomicBool,
+    wakers: Mutex<Vec<Waker>>,
+    children: Mutex<Vec<(usize, Weak<TokenState>)>>,
+    // The slot this state occupies in `parent`'s `children`, used to
+    // deregister itself on drop.
+    parent: Option<(Arc<TokenState>, usize)>,
+}
+
+impl TokenState {
+    fn new(already_cancelled: bool, parent: Option<(Arc<TokenState>, usize)>) -> Self {
+        TokenState {
+            cancelled: AtomicBool::new(already_cancelled),
+            wakers: Mutex::new(Vec::new()),
+            children: Mutex::new(Vec::new()),
+            parent,
+        }
+    }
+
+    fn cancel(&self) {
+        if self.cancelled.swap(true, Ordering::AcqRel) {
+            return; // Already cancelled; don't re-propagate.
+        }
+        for waker in self.wakers.lock().unwrap().drain(..) {
+            waker.wake();
+        }
+        // Propagate to children, pruning any that have since been dropped -
+        // this is belt-and-braces on top of the `Drop` impl below, in case a
+        // child outlives its last strong handle briefly under contention.
+        // The children are cancelled (and their strong handles dropped)
+        // only after the lock is released, because a child's `Drop` locks
+        // this same `children` list.
+        let children: Vec<Arc<TokenState>> = {
+            let mut children = self.children.lock().unwrap();
+            children.retain(|(_, weak)| weak.strong_count() > 0);
+            children.iter().filter_map(|(_, weak)| weak.upgrade()).collect()
+        };
+        for child in children {
+            child.cancel();
+        }
+    }
+}
+
+impl Drop for TokenState {
+    fn drop(&mut self) {
+        // Deregister from the parent so a long-lived root that spawns many
+        // short-lived children doesn't accumulate dead `Weak` entries.
+        if let Some((parent, id)) = &self.parent {
+            parent.children.lock().unwrap().retain(|(slot, _)| slot != id);
+        }
+    }
+}
+
+/// A cheaply-clonable handle into a cancellation tree. Cancelling a token
+/// cancels every descendant created via `child_token()`.
+#[derive(Clone)]
+pub struct CancellationToken {
+    inner: Arc<TokenState>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        CancellationToken {
+            inner: Arc::new(TokenState::new(false, None)),
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::Acquire)
+    }
+
+    pub fn cancel(&self) {
+        self.inner.cancel();
+    }
+
+    /// A token cancelled when either it or `self` (or any ancestor of
+    /// `self`) is cancelled. Dropping the returned token - once nothing else
+    /// holds it - deregisters it from this parent via `TokenState`'s `Drop`
+    /// impl, so a long-lived parent that keeps minting children doesn't grow
+    /// `children` without bound.
+    pub fn child_token(&self) -> CancellationToken {
+        let already_cancelled = self.is_cancelled();
+        let id = NEXT_TOKEN_ID.fetch_add(1, Ordering::Relaxed);
+        let child = Arc::new(TokenState::new(
+            already_cancelled,
+            Some((Arc::clone(&self.inner), id)),
+        ));
+        self.inner.children.lock().unwrap().push((id, Arc::downgrade(&child)));
+        CancellationToken { inner: child }
+    }
+
+    pub fn cancelled(&self) -> Cancelled {
+        Cancelled {
+            inner: self.inner.clone(),
+            waker_slot: None,
+        }
+    }
+}
+
+impl Default for CancellationToken {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Future returned by [`CancellationToken::cancelled`]; resolves once the
+/// token's flag flips.
+pub struct Cancelled {
+    inner: Arc<TokenState>,
+    // Index of this future's own slot in `inner.wakers`, once registered.
+    waker_slot: Option<usize>,
+}
+
+impl Future for Cancelled {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let this = self.get_mut();
+        if this.inner.cancelled.load(Ordering::Acquire) {
+            return Poll::Ready(());
+        }
+
+        // Update this future's own slot rather than pushing a fresh Waker
+        // on every poll - `select!` re-polls pending arms on every wakeup
+        // of a sibling branch, which would otherwise grow `wakers` without
+        // bound over a long-lived fetch.
+        {
+            let mut wakers = this.inner.wakers.lock().unwrap();
+            match this.waker_slot.and_then(|slot| wakers.get_mut(slot)) {
+                Some(waker) if waker.will_wake(cx.waker()) => {}
+                Some(waker) => *waker = cx.waker().clone(),
+                None => {
+                    this.waker_slot = Some(wakers.len());
+                    wakers.push(cx.waker().clone());
+                }
+            }
+        }
+
+        // Re-check in case `cancel()` ran between the first check and
+        // registering the waker above.
+        if this.inner.cancelled.load(Ordering::Acquire) {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+/// Retry/timeout tuning for `get_pokemon`, threaded from `main` so callers
+/// can dial aggressiveness up or down per environment.
+#[derive(Clone, Copy)]
+pub struct FetchConfig {
+    pub per_try_timeout: Duration,
+    pub max_retries: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for FetchConfig {
+    fn default() -> Self {
+        FetchConfig {
+            per_try_timeout: Duration::from_secs(5),
+            max_retries: 3,
+            base_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(5),
+        }
+    }
+}
+
+// `base * 2^attempt`, capped at `max_delay`, plus jitter in `[0, delay/2)`
+// so the concurrently-spawned tasks don't all retry in lockstep.
+fn backoff_with_jitter(config: &FetchConfig, attempt: u32) -> Duration {
+    let exp = config
+        .base_delay
+        .saturating_mul(1u32.checked_shl(attempt).unwrap_or(u32::MAX));
+    let capped = exp.min(config.max_delay);
+    let jitter = rand::thread_rng().gen_range(0..(capped.as_millis() as u64 / 2).max(1));
+    capped + Duration::from_millis(jitter)
+}
+
+enum FetchOutcome {
+    Success(Pokemon),
+    Retryable(String),
+    Fatal(String),
+}
+
+async fn try_fetch_pokemon(id: i32, config: &FetchConfig) -> FetchOutcome {
+    let url = format!("https://pokeapi.co/api/v2/pokemon/{}", id);
+
+    let response = match tokio::time::timeout(
+        config.per_try_timeout,
+        reqwest::Client::new().get(&url).send(),
+    )
+    .await
+    {
+        Err(_) => return FetchOutcome::Retryable(format!("request for {id} timed out")),
+        Ok(Err(e)) if e.is_connect() => {
+            return FetchOutcome::Retryable(format!("connection error for {id}: {e}"))
+        }
+        Ok(Err(e)) => return FetchOutcome::Fatal(format!("request for {id} failed: {e}")),
+        Ok(Ok(response)) => response,
+    };
+
+    let status = response.status();
+    if status == reqwest::StatusCode::NOT_FOUND {
+        return FetchOutcome::Fatal(format!("pokemon {id} does not exist"));
+    }
+    if status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        return FetchOutcome::Retryable(format!("{id} returned {status}"));
+    }
+
+    match response.json::<Pokemon>().await {
+        Ok(pokemon) => FetchOutcome::Success(pokemon),
+        Err(e) => FetchOutcome::Fatal(format!("failed to parse pokemon {id}: {e}")),
+    }
+}
+
+async fn get_pokemon(
+    id: i32,
+    token: &CancellationToken,
+    config: &FetchConfig,
+) -> Result<Pokemon, Box<dyn Error + Send + Sync>> {
+    for attempt in 0..=config.max_retries {
+        let outcome = tokio::select! {
+            outcome = try_fetch_pokemon(id, config) => outcome,
+            _ = token.cancelled() => return Err("fetch cancelled".into()),
+        };
+
+        match outcome {
+            FetchOutcome::Success(pokemon) => return Ok(pokemon),
+            FetchOutcome::Fatal(msg) => return Err(msg.into()),
+            FetchOutcome::Retryable(msg) => {
+                if attempt == config.max_retries {
+                    return Err(msg.into());
+                }
+                tokio::select! {
+                    _ = tokio::time::sleep(backoff_with_jitter(config, attempt)) => {}
+                    _ = token.cancelled() => return Err("fetch cancelled".into()),
+                }
+            }
+        }
+    }
+
+    unreachable!("loop always returns on its last iteration")
+}
+
+// Shared state behind a `TaskTracker`: a live-task counter plus a notifier
+// so `wait()` can sleep instead of polling.
+struct TrackerState {
+    count: AtomicUsize,
+    closed: AtomicBool,
+    notify: tokio::sync::Notify,
+}
+
+/// Tracks a dynamic group of spawned tasks without owning their
+/// `JoinHandle`s, so tasks stay fire-and-forget while still giving callers
+/// a clean join point via `wait()`.
+#[derive(Clone)]
+pub struct TaskTracker {
+    inner: Arc<TrackerState>,
+}
+
+impl TaskTracker {
+    pub fn new() -> Self {
+        TaskTracker {
+            inner: Arc::new(TrackerState {
+                count: AtomicUsize::new(0),
+                closed: AtomicBool::new(false),
+                notify: tokio::sync::Notify::new(),
+            }),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.count.load(Ordering::Acquire)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Marks that no further tasks will be tracked; `wait()` only resolves
+    /// once this has been called.
+    pub fn close(&self) {
+        self.inner.closed.store(true, Ordering::Release);
+        self.inner.notify.notify_waiters();
+    }
+
+    /// Spawns `fut`, tracking its lifetime. The returned task is not
+    /// awaited by the tracker - it decrements the counter and notifies any
+    /// `wait()` callers on its own when it finishes.
+    pub fn track_future<F>(&self, fut: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        self.inner.count.fetch_add(1, Ordering::AcqRel);
+        let state = self.inner.clone();
+        tokio::spawn(async move {
+            fut.await;
+            state.count.fetch_sub(1, Ordering::AcqRel);
+            state.notify.notify_waiters();
+        });
+    }
+
+    /// Resolves once `close()` has been called and every tracked task has
+    /// finished.
+    pub async fn wait(&self) {
+        loop {
+            // Register for a notification before checking the condition, so
+            // a `notify_waiters()` between the check and the `.await` below
+            // can't be missed.
+            let notified = self.inner.notify.notified();
+            if self.inner.closed.load(Ordering::Acquire) && self.len() == 0 {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+impl Default for TaskTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A `JoinMap` pairs every spawned task with the key it was fetching, so a
+/// caller draining results via `join_next` knows exactly which key a
+/// success or failure belongs to instead of losing that association the
+/// way a bare `Vec<JoinHandle<_>>` does.
+struct JoinMap<K, V> {
+    abort_handles: HashMap<K, AbortHandle>,
+    tx: mpsc::UnboundedSender<(K, Result<V, JoinError>)>,
+    rx: mpsc::UnboundedReceiver<(K, Result<V, JoinError>)>,
+    // Spawned tasks whose result `join_next` has not yielded yet. `tx` is
+    // held here, so `rx` alone never reports the end.
+    in_flight: usize,
+}
+
+impl<K, V> JoinMap<K, V>
+where
+    K: Eq + std::hash::Hash + Clone + Send + 'static,
+    V: Send + 'static,
+{
+    fn new() -> Self {
+        let (tx, rx) = mpsc::unbounded_channel();
+        JoinMap {
+            abort_handles: HashMap::new(),
+            tx,
+            rx,
+            in_flight: 0,
+        }
+    }
+
+    /// Spawns `fut` under `key`. A small reaper task forwards the join
+    /// result (including the `JoinError` left behind by `abort`) to
+    /// `join_next` once the task finishes.
+    fn spawn<F>(&mut self, key: K, fut: F)
+    where
+        F: Future<Output = V> + Send + 'static,
+    {
+        let handle = tokio::spawn(fut);
+        self.in_flight += 1;
+        self.abort_handles.insert(key.clone(), handle.abort_handle());
+        let tx = self.tx.clone();
+        tokio::spawn(async move {
+            let _ = tx.send((key, handle.await));
+        });
+    }
+
+    /// Awaits the next task to finish, in completion order, paired with
+    /// the key it was spawned under. Returns `None` once every spawned
+    /// task has been reaped.
+    async fn join_next(&mut self) -> Option<(K, Result<V, JoinError>)> {
+        if self.in_flight == 0 {
+            return None;
+        }
+        let (key, result) = self.rx.recv().await?;
+        self.in_flight -= 1;
+        self.abort_handles.remove(&key);
+        Some((key, result))
+    }
+
+    /// Aborts the in-flight task for `key`, if any is still running.
+    fn abort(&mut self, key: &K) -> bool {
+        match self.abort_handles.remove(key) {
+            Some(handle) => {
+                handle.abort();
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn abort_all(&mut self) {
+        for (_, handle) in self.abort_handles.drain() {
+            handle.abort();
+        }
+    }
+}
+
+async fn buscar_pokemons(ids: Vec<i32>, token: CancellationToken, config: FetchConfig) -> Vec<Pokemon> {
+    let mut joins = JoinMap::new();
+    for id in ids {
+        let token = token.clone();
+        joins.spawn(id, async move { get_pokemon(id, &token, &config).await });
+    }
+
+    let mut res = vec![];
+    while let Some((id, result)) = joins.join_next().await {
+        match result {
+            Ok(Ok(pokemon)) => res.push(pokemon),
+            Ok(Err(e)) => eprintln!("pokemon {id} failed: {e}"),
+            Err(e) => eprintln!("pokemon {id} task failed: {e}"),
+        }
+    }
+    res
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() < 2 {
+        return Err("Missing output path argument.".into());
+    }
+
+    let chunks: Vec<Vec<i32>> = (1..=30)
+        .collect::<Vec<_>>()
+        .chunks(10)
+        .map(|c| c.to_vec())
+        .collect();
+
+    // Root token for the whole fetch; each chunk gets its own child so one
+    // chunk could be cancelled independently without affecting the others.
+    let root_token = CancellationToken::new();
+
+    let pokes = Arc::new(Mutex::new(Vec::new()));
+    let tracker = TaskTracker::new();
+    let config = FetchConfig::default();
+
+    for ids in chunks {
+        let pokes = Arc::clone(&pokes);
+        let token = root_token.child_token();
+        tracker.track_future(async move {
+            let res = buscar_pokemons(ids, token, config).await;
+            pokes.lock().unwrap().extend(res);
+        });
+    }
+
+    tracker.close();
+    println!("Waiting on {} in-flight chunk(s)...", tracker.len());
+    tracker.wait().await;
+
+    let fin = Arc::try_unwrap(pokes).unwrap().into_inner().unwrap();
+    let json = serde_json::to_string_pretty(&fin)?;
+    File::create(&args[1])?.write_all(json.as_bytes())?;
+
+    Ok(())
+}
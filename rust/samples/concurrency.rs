@@ -109,8 +109,9 @@ use std::sync::{Arc, Mutex};
 use std::collections::VecDeque;
 
 struct ThreadPool {
-    workers: Vec<thread::JoinHandle<()>>,
-    sender: std::sync::mpsc::Sender<Job>,
+    // `Option` so `drop`/`shutdown` can `take()` each handle and join it exactly once.
+    workers: Vec<Option<thread::JoinHandle<()>>>,
+    sender: Option<std::sync::mpsc::Sender<Job>>,
 }
 
 type Job = Box<dyn FnOnce() + Send + 'static>;
@@ -119,9 +120,9 @@ impl ThreadPool {
     fn new(size: usize) -> Self {
         let (sender, receiver) = std::sync::mpsc::channel();
         let receiver = Arc::new(Mutex::new(receiver));
-        
+
         let mut workers = Vec::with_capacity(size);
-        
+
         for id in 0..size {
             let receiver = Arc::clone(&receiver);
             let worker = thread::spawn(move || loop {
@@ -137,29 +138,311 @@ impl ThreadPool {
                     }
                 }
             });
-            workers.push(worker);
+            workers.push(Some(worker));
         }
-        
-        ThreadPool { workers, sender }
+
+        ThreadPool { workers, sender: Some(sender) }
     }
-    
+
     fn execute<F>(&self, f: F)
     where
         F: FnOnce() + Send + 'static,
     {
         let job = Box::new(f);
-        self.sender.send(job).unwrap();
+        self.sender.as_ref().unwrap().send(job).unwrap();
+    }
+
+    // Blocks until every job queued so far has been picked up and finished,
+    // without tearing the pool down. Workers keep running afterwards.
+    fn join(&self) {
+        // One marker job per worker, each blocking on a shared barrier: no
+        // worker can hold two markers, so the barrier only opens once every
+        // worker has finished whatever it was running and taken one.
+        let barrier = Arc::new(std::sync::Barrier::new(self.workers.len() + 1));
+        for _ in &self.workers {
+            let barrier = Arc::clone(&barrier);
+            self.execute(move || {
+                barrier.wait();
+            });
+        }
+        barrier.wait();
+    }
+
+    // Consumes the pool, disconnecting the channel and joining every worker.
+    fn shutdown(mut self) {
+        self.shutdown_mut();
+    }
+
+    fn shutdown_mut(&mut self) {
+        // Dropping the sender disconnects the channel, so each worker's
+        // `recv()` returns `Err` and its loop breaks.
+        self.sender.take();
+
+        for worker in &mut self.workers {
+            if let Some(handle) = worker.take() {
+                handle.join().unwrap();
+            }
+        }
     }
 }
 
 impl Drop for ThreadPool {
     fn drop(&mut self) {
-        // Drop sender to signal workers to stop
-        // Workers will join automatically
+        self.shutdown_mut();
     }
 }
 
 
+//// Work-Stealing Executor, each worker owns a local deque instead of contending on one shared receiver
+use std::sync::atomic::{AtomicIsize, AtomicPtr, Ordering};
+
+// A Chase-Lev lock-free work-stealing deque. The owner pushes/pops from the
+// `bottom` end; any number of thieves may concurrently `steal` from `top`.
+struct ChaseLevDeque<T> {
+    top: AtomicIsize,
+    bottom: AtomicIsize,
+    buffer: AtomicPtr<Buffer<T>>,
+}
+
+struct Buffer<T> {
+    mask: isize,
+    storage: Box<[std::cell::UnsafeCell<std::mem::MaybeUninit<T>>]>,
+}
+
+impl<T> Buffer<T> {
+    fn new(capacity: usize) -> Self {
+        let capacity = capacity.next_power_of_two();
+        Self {
+            mask: capacity as isize - 1,
+            storage: (0..capacity)
+                .map(|_| std::cell::UnsafeCell::new(std::mem::MaybeUninit::uninit()))
+                .collect(),
+        }
+    }
+
+    unsafe fn read(&self, index: isize) -> T {
+        (*self.storage[(index & self.mask) as usize].get()).as_ptr().read()
+    }
+
+    unsafe fn write(&self, index: isize, value: T) {
+        (*self.storage[(index & self.mask) as usize].get()).as_mut_ptr().write(value);
+    }
+
+    // A fresh, double-capacity buffer holding the still-live `[top, bottom)`
+    // range copied across from `self`.
+    fn grow(&self, top: isize, bottom: isize) -> Self {
+        let grown = Buffer::new((self.mask as usize + 1) * 2);
+        for index in top..bottom {
+            unsafe { grown.write(index, self.read(index)) };
+        }
+        grown
+    }
+}
+
+enum Steal<T> {
+    Empty,
+    Abort,
+    Success(T),
+}
+
+impl<T> ChaseLevDeque<T> {
+    fn new(capacity: usize) -> Self {
+        Self {
+            top: AtomicIsize::new(0),
+            bottom: AtomicIsize::new(0),
+            buffer: AtomicPtr::new(Box::into_raw(Box::new(Buffer::new(capacity)))),
+        }
+    }
+
+    // Only the owning worker thread may call `push`.
+    fn push(&self, value: T) {
+        let bottom = self.bottom.load(Ordering::Relaxed);
+        let top = self.top.load(Ordering::Acquire);
+        let buffer = unsafe { &*self.buffer.load(Ordering::Relaxed) };
+
+        let buffer = if bottom - top >= buffer.mask + 1 {
+            // Full: grow into a fresh, larger buffer and copy the live
+            // range across, then swap it in. The old buffer is
+            // intentionally leaked rather than freed here - a concurrent
+            // `steal` may still be mid-read from it, and reclaiming it
+            // safely needs epoch-based GC this sample doesn't implement.
+            let grown = Box::into_raw(Box::new(buffer.grow(top, bottom)));
+            self.buffer.store(grown, Ordering::Release);
+            unsafe { &*grown }
+        } else {
+            buffer
+        };
+
+        unsafe { buffer.write(bottom, value) };
+        // Publish the slot before advancing `bottom` so thieves never see a half-written value.
+        self.bottom.store(bottom + 1, Ordering::Release);
+    }
+
+    // Only the owning worker thread may call `pop`.
+    fn pop(&self) -> Option<T> {
+        let bottom = self.bottom.load(Ordering::Relaxed) - 1;
+        let buffer = unsafe { &*self.buffer.load(Ordering::Relaxed) };
+        self.bottom.store(bottom, Ordering::Relaxed);
+
+        let top = self.top.load(Ordering::SeqCst);
+        let size = bottom - top;
+
+        if size < 0 {
+            // Queue was already empty; restore `bottom`.
+            self.bottom.store(top, Ordering::Relaxed);
+            return None;
+        }
+
+        let value = unsafe { buffer.read(bottom) };
+        if size > 0 {
+            // More than one element: no race with a thief is possible.
+            return Some(value);
+        }
+
+        // Exactly one element left: race a thief for it via `top`.
+        if self
+            .top
+            .compare_exchange(top, top + 1, Ordering::SeqCst, Ordering::Relaxed)
+            .is_err()
+        {
+            // Lost the race; a thief already took it. Leave the deque empty.
+            std::mem::forget(value);
+            self.bottom.store(top + 1, Ordering::Relaxed);
+            return None;
+        }
+        self.bottom.store(top + 1, Ordering::Relaxed);
+        Some(value)
+    }
+
+    fn steal(&self) -> Steal<T> {
+        let top = self.top.load(Ordering::Acquire);
+        let bottom = self.bottom.load(Ordering::Acquire);
+
+        if top >= bottom {
+            return Steal::Empty;
+        }
+
+        let buffer = unsafe { &*self.buffer.load(Ordering::Acquire) };
+        let value = unsafe { buffer.read(top) };
+
+        match self
+            .top
+            .compare_exchange(top, top + 1, Ordering::SeqCst, Ordering::Relaxed)
+        {
+            Ok(_) => Steal::Success(value),
+            Err(_) => {
+                std::mem::forget(value);
+                Steal::Abort
+            }
+        }
+    }
+}
+
+impl<T> Drop for ChaseLevDeque<T> {
+    fn drop(&mut self) {
+        // Drain whatever is left so we don't leak unfinished jobs, then free the buffer.
+        while self.pop().is_some() {}
+        unsafe { drop(Box::from_raw(self.buffer.load(Ordering::Relaxed))) };
+    }
+}
+
+thread_local! {
+    // Set for the duration of a worker's loop so a job running on that
+    // worker can submit sub-tasks directly to its own local deque instead
+    // of going through the (then-uncontended) injector.
+    static CURRENT_DEQUE: RefCell<Option<Arc<ChaseLevDeque<Job>>>> = RefCell::new(None);
+}
+
+// A global injector plus one Chase-Lev deque per worker: workers push/pop
+// locally (no contention) and fall back to stealing from siblings, or from
+// the injector, only once their own deque runs dry.
+struct WorkStealingPool {
+    injector: Arc<Mutex<VecDeque<Job>>>,
+    deques: Vec<Arc<ChaseLevDeque<Job>>>,
+    workers: Vec<Option<thread::JoinHandle<()>>>,
+    running: Arc<std::sync::atomic::AtomicBool>,
+}
+
+impl WorkStealingPool {
+    fn new(size: usize) -> Self {
+        let injector = Arc::new(Mutex::new(VecDeque::new()));
+        let deques: Vec<_> = (0..size).map(|_| Arc::new(ChaseLevDeque::new(256))).collect();
+        let running = Arc::new(std::sync::atomic::AtomicBool::new(true));
+
+        let workers = (0..size)
+            .map(|id| {
+                let own = Arc::clone(&deques[id]);
+                let siblings: Vec<_> = deques
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| *i != id)
+                    .map(|(_, d)| Arc::clone(d))
+                    .collect();
+                let injector = Arc::clone(&injector);
+                let running = Arc::clone(&running);
+
+                Some(thread::spawn(move || {
+                    CURRENT_DEQUE.with(|cell| *cell.borrow_mut() = Some(Arc::clone(&own)));
+
+                    while running.load(Ordering::Relaxed) {
+                        if let Some(job) = own.pop() {
+                            job();
+                            continue;
+                        }
+                        if let Some(job) = injector.lock().unwrap().pop_front() {
+                            job();
+                            continue;
+                        }
+                        let mut stole = false;
+                        for sibling in &siblings {
+                            match sibling.steal() {
+                                Steal::Success(job) => {
+                                    job();
+                                    stole = true;
+                                    break;
+                                }
+                                Steal::Empty | Steal::Abort => continue,
+                            }
+                        }
+                        if !stole {
+                            thread::yield_now();
+                        }
+                    }
+                }))
+            })
+            .collect();
+
+        WorkStealingPool { injector, deques, workers, running }
+    }
+
+    // Submitted from outside any worker, work goes to the global injector.
+    // Called from inside a running job, it lands on that worker's own local
+    // deque instead, so siblings that run dry actually have something to
+    // steal from.
+    fn execute<F>(&self, f: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        let job: Job = Box::new(f);
+        let own = CURRENT_DEQUE.with(|cell| cell.borrow().clone());
+        match own {
+            Some(own) => own.push(job),
+            None => self.injector.lock().unwrap().push_back(job),
+        }
+    }
+}
+
+impl Drop for WorkStealingPool {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        for worker in &mut self.workers {
+            if let Some(handle) = worker.take() {
+                let _ = handle.join();
+            }
+        }
+    }
+}
+
 
 //// Message Passing (std::sync::mpsc), multi-producer, single-consumer channels, thread-safe messaging, ownership is transfered
 use std::sync::mpsc;
@@ -290,6 +573,297 @@ match rx.recv_timeout(Duration::from_secs(1)) {
 }
 
 
+/// Bounded MPMC Channel: std::sync::mpsc is single-consumer; this is Dmitry Vyukov's
+/// lock-free bounded queue for when several consumers must pull from one buffer.
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+struct Cell<T> {
+    sequence: AtomicUsize,
+    value: std::cell::UnsafeCell<std::mem::MaybeUninit<T>>,
+}
+
+struct MpmcQueue<T> {
+    mask: usize,
+    buffer: Box<[Cell<T>]>,
+    enqueue_pos: AtomicUsize,
+    dequeue_pos: AtomicUsize,
+}
+
+unsafe impl<T: Send> Send for MpmcQueue<T> {}
+unsafe impl<T: Send> Sync for MpmcQueue<T> {}
+
+impl<T> MpmcQueue<T> {
+    fn new(capacity: usize) -> Self {
+        let capacity = capacity.next_power_of_two();
+        let buffer = (0..capacity)
+            .map(|i| Cell {
+                sequence: AtomicUsize::new(i),
+                value: std::cell::UnsafeCell::new(std::mem::MaybeUninit::uninit()),
+            })
+            .collect();
+        Self {
+            mask: capacity - 1,
+            buffer,
+            enqueue_pos: AtomicUsize::new(0),
+            dequeue_pos: AtomicUsize::new(0),
+        }
+    }
+
+    fn try_send(&self, value: T) -> Result<(), T> {
+        let mut pos = self.enqueue_pos.load(Ordering::Relaxed);
+        loop {
+            let cell = &self.buffer[pos & self.mask];
+            let seq = cell.sequence.load(Ordering::Acquire);
+            let diff = seq as isize - pos as isize;
+
+            if diff == 0 {
+                match self.enqueue_pos.compare_exchange_weak(
+                    pos,
+                    pos + 1,
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => {
+                        unsafe { (*cell.value.get()).as_mut_ptr().write(value) };
+                        cell.sequence.store(pos + 1, Ordering::Release);
+                        return Ok(());
+                    }
+                    Err(actual) => pos = actual,
+                }
+            } else if diff < 0 {
+                return Err(value); // full
+            } else {
+                pos = self.enqueue_pos.load(Ordering::Relaxed);
+            }
+        }
+    }
+
+    fn try_recv(&self) -> Option<T> {
+        let mut pos = self.dequeue_pos.load(Ordering::Relaxed);
+        loop {
+            let cell = &self.buffer[pos & self.mask];
+            let seq = cell.sequence.load(Ordering::Acquire);
+            let diff = seq as isize - (pos + 1) as isize;
+
+            if diff == 0 {
+                match self.dequeue_pos.compare_exchange_weak(
+                    pos,
+                    pos + 1,
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => {
+                        let value = unsafe { (*cell.value.get()).as_ptr().read() };
+                        cell.sequence.store(pos + self.buffer.len(), Ordering::Release);
+                        return Some(value);
+                    }
+                    Err(actual) => pos = actual,
+                }
+            } else if diff < 0 {
+                return None; // empty
+            } else {
+                pos = self.dequeue_pos.load(Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+struct MpmcSender<T> {
+    queue: Arc<MpmcQueue<T>>,
+}
+
+#[derive(Clone)]
+struct MpmcReceiver<T> {
+    queue: Arc<MpmcQueue<T>>,
+}
+
+impl<T> MpmcSender<T> {
+    fn try_send(&self, value: T) -> Result<(), T> {
+        self.queue.try_send(value)
+    }
+
+    // Blocking send: spins with a short back-off until a slot frees up.
+    fn send(&self, mut value: T) {
+        loop {
+            match self.queue.try_send(value) {
+                Ok(()) => return,
+                Err(v) => {
+                    value = v;
+                    thread::yield_now();
+                }
+            }
+        }
+    }
+}
+
+impl<T> MpmcReceiver<T> {
+    fn try_recv(&self) -> Option<T> {
+        self.queue.try_recv()
+    }
+
+    fn recv(&self) -> T {
+        loop {
+            if let Some(value) = self.queue.try_recv() {
+                return value;
+            }
+            thread::yield_now();
+        }
+    }
+
+    fn recv_timeout(&self, timeout: Duration) -> Result<T, RecvTimeoutError> {
+        let deadline = std::time::Instant::now() + timeout;
+        loop {
+            if let Some(value) = self.queue.try_recv() {
+                return Ok(value);
+            }
+            if std::time::Instant::now() >= deadline {
+                return Err(RecvTimeoutError::Timeout);
+            }
+            thread::yield_now();
+        }
+    }
+}
+
+fn bounded_mpmc<T>(capacity: usize) -> (MpmcSender<T>, MpmcReceiver<T>) {
+    let queue = Arc::new(MpmcQueue::new(capacity));
+    (
+        MpmcSender { queue: Arc::clone(&queue) },
+        MpmcReceiver { queue },
+    )
+}
+
+
+/// Select: wait on several channels at once instead of funneling everything
+/// into one Receiver like the "Multiple Producers" example above.
+use std::sync::Condvar;
+use std::any::Any;
+
+// Shared "something is ready" token: every registered channel's sender
+// notifies the same condvar, so the selecting thread can park on one thing.
+type ReadyToken = Arc<(Mutex<bool>, Condvar)>;
+
+fn ready_token() -> ReadyToken {
+    Arc::new((Mutex::new(false), Condvar::new()))
+}
+
+struct SelectSender<T> {
+    sender: mpsc::Sender<T>,
+    token: ReadyToken,
+}
+
+impl<T> SelectSender<T> {
+    fn send(&self, value: T) -> Result<(), mpsc::SendError<T>> {
+        self.sender.send(value)?;
+        let (ready, cvar) = &*self.token;
+        *ready.lock().unwrap() = true;
+        cvar.notify_all();
+        Ok(())
+    }
+}
+
+// Type-erased so `Select` can hold receivers of different message types.
+trait SelectableChannel: Send {
+    fn try_recv_any(&self) -> Option<Box<dyn Any + Send>>;
+}
+
+struct SelectReceiver<T> {
+    receiver: mpsc::Receiver<T>,
+}
+
+impl<T: Send + 'static> SelectableChannel for SelectReceiver<T> {
+    fn try_recv_any(&self) -> Option<Box<dyn Any + Send>> {
+        self.receiver.try_recv().ok().map(|v| Box::new(v) as Box<dyn Any + Send>)
+    }
+}
+
+fn selectable_channel<T>(token: ReadyToken) -> (SelectSender<T>, SelectReceiver<T>) {
+    let (sender, receiver) = mpsc::channel();
+    (SelectSender { sender, token }, SelectReceiver { receiver })
+}
+
+struct Select {
+    token: ReadyToken,
+    channels: Vec<Box<dyn SelectableChannel>>,
+    // Rotates which registrant is polled first, so one busy channel can't starve the rest.
+    start: usize,
+}
+
+impl Select {
+    fn new(token: ReadyToken) -> Self {
+        Self { token, channels: Vec::new(), start: 0 }
+    }
+
+    fn register<T: Send + 'static>(&mut self, receiver: SelectReceiver<T>) -> usize {
+        self.channels.push(Box::new(receiver));
+        self.channels.len() - 1
+    }
+
+    // Polls every registered channel in rotated order, returning the first ready one.
+    fn poll_once(&mut self) -> Option<(usize, Box<dyn Any + Send>)> {
+        let n = self.channels.len();
+        for offset in 0..n {
+            let idx = (self.start + offset) % n;
+            if let Some(msg) = self.channels[idx].try_recv_any() {
+                self.start = (idx + 1) % n;
+                return Some((idx, msg));
+            }
+        }
+        None
+    }
+
+    // Blocks until any registered channel has a message.
+    fn select(&mut self) -> (usize, Box<dyn Any + Send>) {
+        loop {
+            if let Some(result) = self.poll_once() {
+                return result;
+            }
+
+            let (ready, cvar) = &*self.token;
+            let mut guard = ready.lock().unwrap();
+            while !*guard {
+                guard = cvar.wait(guard).unwrap();
+            }
+            *guard = false;
+        }
+    }
+
+    // As `select`, but gives up and returns `None` after `timeout`.
+    fn select_timeout(&mut self, timeout: Duration) -> Option<(usize, Box<dyn Any + Send>)> {
+        let deadline = std::time::Instant::now() + timeout;
+        loop {
+            if let Some(result) = self.poll_once() {
+                return Some(result);
+            }
+
+            let remaining = deadline.checked_duration_since(std::time::Instant::now())?;
+            let (ready, cvar) = &*self.token;
+            let guard = ready.lock().unwrap();
+            let (mut guard, timed_out) = cvar.wait_timeout_while(guard, remaining, |r| !*r).unwrap();
+            if timed_out.timed_out() {
+                return None;
+            }
+            *guard = false;
+        }
+    }
+}
+
+// A `select!`-like macro: `select_on!(select, 0 => msg: i32 => { .. }, 1 => msg: String => { .. })`
+// downcasts the winning arm's message to the annotated type before running its body.
+macro_rules! select_on {
+    ($select:expr, $( $idx:pat => $binding:ident : $ty:ty => $body:block ),+ $(,)?) => {{
+        let (index, message) = $select.select();
+        match index {
+            $( $idx => {
+                let $binding = *message.downcast::<$ty>().expect("select arm type mismatch");
+                $body
+            } )+
+            _ => unreachable!("no registered channel at that index"),
+        }
+    }};
+}
+
+
 //// Shared State Concurrency
 /// Mutex Basics
 use std::sync::{Arc, Mutex};
@@ -426,6 +1000,157 @@ while !*started {
 println!("Main thread woke up!");
 
 
+/// FairMutex: plain `Mutex`/`Condvar` above give no ordering guarantee between
+/// waiters and can starve one thread indefinitely under contention. This one
+/// queues waiters in arrival order and parks/unparks the specific thread
+/// whose turn it is, so both lock acquisition and condition waits are FIFO.
+use std::collections::VecDeque;
+use std::sync::atomic::AtomicBool;
+use std::thread::Thread;
+
+struct FairMutex<T> {
+    locked: AtomicBool,
+    // Threads blocked trying to acquire the mutex, oldest first.
+    lock_waiters: Mutex<VecDeque<Thread>>,
+    // Threads blocked in `wait_while`, unrelated to lock acquisition itself.
+    cond_waiters: Mutex<VecDeque<Thread>>,
+    data: std::cell::UnsafeCell<T>,
+}
+
+unsafe impl<T: Send> Send for FairMutex<T> {}
+unsafe impl<T: Send> Sync for FairMutex<T> {}
+
+struct FairMutexGuard<'a, T> {
+    mutex: &'a FairMutex<T>,
+}
+
+impl<T> FairMutex<T> {
+    fn new(value: T) -> Self {
+        Self {
+            locked: AtomicBool::new(false),
+            lock_waiters: Mutex::new(VecDeque::new()),
+            cond_waiters: Mutex::new(VecDeque::new()),
+            data: std::cell::UnsafeCell::new(value),
+        }
+    }
+
+    fn lock(&self) -> FairMutexGuard<'_, T> {
+        if self
+            .locked
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_ok()
+        {
+            return FairMutexGuard { mutex: self };
+        }
+
+        // Queue in arrival order and park. `unlock` hands ownership directly
+        // to the front of this queue, so whoever wakes us already owns the lock.
+        let me = thread::current();
+        {
+            let mut waiters = self.lock_waiters.lock().unwrap();
+            // Retry under the queue lock: an `unlock` that ran since the
+            // first CAS has either freed the lock or will see us queued.
+            if self
+                .locked
+                .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+                .is_ok()
+            {
+                return FairMutexGuard { mutex: self };
+            }
+            waiters.push_back(me.clone());
+        }
+        // `unlock` removes us from the queue before unparking; a spurious
+        // wakeup finds us still queued and parks again.
+        while self.lock_waiters.lock().unwrap().iter().any(|t| t.id() == me.id()) {
+            thread::park();
+        }
+        FairMutexGuard { mutex: self }
+    }
+
+    fn unlock(&self) {
+        let mut waiters = self.lock_waiters.lock().unwrap();
+        if let Some(next) = waiters.pop_front() {
+            // Direct handoff: `locked` stays true, so a newcomer's CAS still
+            // fails and it queues up behind `next` instead of stealing the lock.
+            next.unpark();
+        } else {
+            self.locked.store(false, Ordering::Release);
+        }
+    }
+
+    fn notify_one(&self) {
+        if let Some(waiter) = self.cond_waiters.lock().unwrap().pop_front() {
+            waiter.unpark();
+        }
+    }
+
+    fn notify_all(&self) {
+        for waiter in self.cond_waiters.lock().unwrap().drain(..) {
+            waiter.unpark();
+        }
+    }
+}
+
+impl<'a, T> std::ops::Deref for FairMutexGuard<'a, T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        unsafe { &*self.mutex.data.get() }
+    }
+}
+
+impl<'a, T> std::ops::DerefMut for FairMutexGuard<'a, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.mutex.data.get() }
+    }
+}
+
+impl<'a, T> Drop for FairMutexGuard<'a, T> {
+    fn drop(&mut self) {
+        self.mutex.unlock();
+    }
+}
+
+impl<'a, T> FairMutexGuard<'a, T> {
+    // Releases the lock, parks on the condition, and re-acquires in FIFO
+    // position before re-checking `predicate` -- re-checking on every wake
+    // defeats the spurious-wakeup problem plain `park`/`unpark` would have.
+    fn wait_while<F>(mut self, mut predicate: F) -> Self
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        while predicate(&mut *self) {
+            let mutex = self.mutex;
+            mutex.cond_waiters.lock().unwrap().push_back(thread::current());
+            drop(self); // releases the lock, waking the next lock-waiter (if any)
+            thread::park();
+            self = mutex.lock();
+        }
+        self
+    }
+
+    fn wait_timeout_while<F>(mut self, timeout: Duration, mut predicate: F) -> (Self, bool)
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        let deadline = std::time::Instant::now() + timeout;
+        while predicate(&mut *self) {
+            let mutex = self.mutex;
+            mutex.cond_waiters.lock().unwrap().push_back(thread::current());
+            drop(self);
+
+            let remaining = deadline.saturating_duration_since(std::time::Instant::now());
+            thread::park_timeout(remaining);
+            self = mutex.lock();
+
+            if std::time::Instant::now() >= deadline {
+                return (self, true);
+            }
+        }
+        (self, false)
+    }
+}
+
+
 /// Barriers
 use std::sync::{Arc, Barrier};
 
@@ -683,4 +1408,340 @@ fn share_between_threads<T: Sync>(data: &T) {
     let handle2 = thread::spawn(|| {
         // Can also safely read from data
     });
+}
+
+
+//// SpinLock: the atomic machinery underneath Mutex/RwLock
+/// A naive spinlock that only `load`s/`store`s with `Relaxed` ordering is
+/// racy: nothing stops the compiler or CPU from reordering the critical
+/// section's writes past the unlock, so a second thread that observes the
+/// flag as unlocked can still read stale data. `compare_exchange_weak` with
+/// `Acquire` on success and a `Release` unlock closes that gap -- together
+/// they establish a happens-before edge from the unlocking thread's writes
+/// to the next acquirer's reads, the same guarantee `Mutex` gives you.
+mod sync {
+    use std::cell::UnsafeCell;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    pub struct SpinLock<T> {
+        locked: AtomicBool,
+        value: UnsafeCell<T>,
+    }
+
+    // Safe to share across threads as long as `T` is `Send`: only one
+    // thread at a time ever gets a `&mut T` into the `UnsafeCell`, exactly
+    // like `Mutex<T>`'s bound.
+    unsafe impl<T: Send> Sync for SpinLock<T> {}
+
+    impl<T> SpinLock<T> {
+        pub fn new(value: T) -> Self {
+            Self {
+                locked: AtomicBool::new(false),
+                value: UnsafeCell::new(value),
+            }
+        }
+
+        /// Spins until the lock is acquired, runs `f` against the
+        /// protected value, then releases.
+        pub fn with_lock<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+            while self
+                .locked
+                .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+                .is_err()
+            {
+                std::hint::spin_loop();
+            }
+
+            // Safety: the compare_exchange above is the only way `locked`
+            // goes false -> true, so we're the sole holder of `&mut T`.
+            let result = f(unsafe { &mut *self.value.get() });
+
+            self.locked.store(false, Ordering::Release);
+            result
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::SpinLock;
+        use std::sync::Arc;
+        use std::thread;
+
+        #[test]
+        fn many_threads_increment_a_shared_counter() {
+            let lock = Arc::new(SpinLock::new(0u64));
+            let threads = 16;
+            let increments_per_thread = 10_000;
+
+            let handles: Vec<_> = (0..threads)
+                .map(|_| {
+                    let lock = Arc::clone(&lock);
+                    thread::spawn(move || {
+                        for _ in 0..increments_per_thread {
+                            lock.with_lock(|count| *count += 1);
+                        }
+                    })
+                })
+                .collect();
+
+            for handle in handles {
+                handle.join().unwrap();
+            }
+
+            lock.with_lock(|count| {
+                assert_eq!(*count, threads * increments_per_thread);
+            });
+        }
+    }
+
+    // Single-thread vs multi-thread smart-pointer aliases, after rustc's
+    // `rustc_data_structures::sync` layer: write graph/tree code once
+    // against `Lrc`/`Lock`/`MTLock`, and flip the `parallel` feature to
+    // swap `Rc`/`RefCell`/a plain cell for `Arc`/`Mutex` without touching
+    // call sites. Exactly one of the two `Lrc`/`Lock` definitions below is
+    // compiled at a time, so nothing here picks a branch at runtime.
+    #[cfg(not(feature = "parallel"))]
+    pub type Lrc<T> = std::rc::Rc<T>;
+    #[cfg(feature = "parallel")]
+    pub type Lrc<T> = std::sync::Arc<T>;
+
+    #[cfg(not(feature = "parallel"))]
+    pub struct Lock<T>(std::cell::RefCell<T>);
+    #[cfg(feature = "parallel")]
+    pub struct Lock<T>(std::sync::Mutex<T>);
+
+    impl<T> Lock<T> {
+        #[cfg(not(feature = "parallel"))]
+        pub fn new(value: T) -> Self {
+            Lock(std::cell::RefCell::new(value))
+        }
+        #[cfg(feature = "parallel")]
+        pub fn new(value: T) -> Self {
+            Lock(std::sync::Mutex::new(value))
+        }
+
+        /// Mutable access; a `RefMut` when `parallel` is off, a
+        /// `MutexGuard` when it's on -- different types, same call site.
+        #[cfg(not(feature = "parallel"))]
+        pub fn lock(&self) -> std::cell::RefMut<'_, T> {
+            self.0.borrow_mut()
+        }
+        #[cfg(feature = "parallel")]
+        pub fn lock(&self) -> std::sync::MutexGuard<'_, T> {
+            self.0.lock().unwrap()
+        }
+
+        /// Read-only access; same story as `lock`, just immutable.
+        #[cfg(not(feature = "parallel"))]
+        pub fn borrow(&self) -> std::cell::Ref<'_, T> {
+            self.0.borrow()
+        }
+        #[cfg(feature = "parallel")]
+        pub fn borrow(&self) -> std::sync::MutexGuard<'_, T> {
+            self.0.lock().unwrap()
+        }
+
+        pub fn with_lock<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+            f(&mut self.lock())
+        }
+    }
+
+    /// A single-writer cell for data that's either confined to one thread
+    /// (a trivial non-atomic cell) or shared across many (a `Mutex`).
+    /// Shares `Lock`'s shape exactly, so it's just an alias.
+    pub type MTLock<T> = Lock<T>;
+
+    /// A small graph node shared by `Lrc`, mutated through `Lock`, that
+    /// builds identically regardless of the `parallel` feature.
+    pub struct GraphNode {
+        pub value: i32,
+        pub children: Vec<Lrc<Lock<GraphNode>>>,
+    }
+
+    impl GraphNode {
+        pub fn leaf(value: i32) -> Lrc<Lock<Self>> {
+            Lrc::new(Lock::new(GraphNode {
+                value,
+                children: Vec::new(),
+            }))
+        }
+
+        pub fn add_child(parent: &Lrc<Lock<Self>>, child: Lrc<Lock<Self>>) {
+            parent.lock().children.push(child);
+        }
+
+        pub fn sum(node: &Lrc<Lock<Self>>) -> i32 {
+            let node = node.borrow();
+            node.value + node.children.iter().map(GraphNode::sum).sum::<i32>()
+        }
+    }
+
+    #[cfg(test)]
+    mod parallel_abstraction_tests {
+        use super::{GraphNode, Lock, Lrc, MTLock};
+
+        #[test]
+        fn graph_sum_is_independent_of_the_parallel_feature() {
+            let root = GraphNode::leaf(1);
+            GraphNode::add_child(&root, GraphNode::leaf(2));
+            GraphNode::add_child(&root, GraphNode::leaf(3));
+
+            assert_eq!(GraphNode::sum(&root), 6);
+        }
+
+        #[test]
+        fn lock_and_mtlock_expose_the_same_with_lock_api() {
+            let lock: Lock<i32> = Lock::new(0);
+            lock.with_lock(|v| *v += 1);
+            assert_eq!(*lock.borrow(), 1);
+
+            let mt: MTLock<Vec<i32>> = MTLock::new(Vec::new());
+            mt.with_lock(|v| v.push(42));
+            assert_eq!(mt.borrow().len(), 1);
+
+            // `Lrc` is a `Clone` smart pointer either way.
+            let shared: Lrc<Lock<i32>> = Lrc::new(Lock::new(10));
+            let shared2 = Lrc::clone(&shared);
+            shared2.with_lock(|v| *v += 5);
+            assert_eq!(*shared.borrow(), 15);
+        }
+    }
+}
+
+// Run the alias tests under both configurations:
+//   cargo test
+//   cargo test --features parallel
+
+
+
+//// LockedBy<L, T>: a field protected by a lock that lives in a different struct
+/// Some structs don't own the lock that protects their fields - the lock
+/// lives on a sibling struct instead (the kernel `struct_mutex` pattern,
+/// and rustc's own `LockedBy`). `&T` access is only sound while that other
+/// lock is actually held, but nothing in the type system enforces it by
+/// itself. `LockedBy` closes the gap at runtime: callers must present a
+/// `MutexGuard<'_, L>`, and accessors debug-assert it's a guard for the
+/// same lock this value was registered against before handing out a
+/// reference - so holding the wrong lock panics in debug builds instead of
+/// silently racing in release.
+mod locked_by {
+    use std::cell::UnsafeCell;
+    use std::sync::MutexGuard;
+
+    pub struct LockedBy<L, T> {
+        value: UnsafeCell<T>,
+        // The address of the data a `MutexGuard<'_, L>` derefs to never
+        // moves across lock/unlock cycles (it lives inside the `Mutex`
+        // itself), so it works as a stable fingerprint for "which lock" -
+        // this is never dereferenced, only compared. That does mean the
+        // owning `Mutex` must already be at its final address (e.g.
+        // already behind its `Arc`) before any `LockedBy` registers
+        // against it; moving the `Mutex` afterwards changes this address
+        // out from under every value already registered.
+        owner: *const L,
+    }
+
+    // Safety: `owner` is never read through, only compared by address, and
+    // every real access to `value` happens behind `check_owner`, which
+    // requires proof (a live guard) that the owning lock is held.
+    unsafe impl<L, T: Send> Send for LockedBy<L, T> {}
+    unsafe impl<L, T: Send> Sync for LockedBy<L, T> {}
+
+    impl<L, T> LockedBy<L, T> {
+        /// Registers `value` as owned by whichever lock `guard` was taken
+        /// from. Requiring a live guard here (rather than a bare `&Mutex<L>`)
+        /// is what lets the owner fingerprint be the protected data's own
+        /// address instead of the `Mutex`'s.
+        pub fn new(guard: &MutexGuard<'_, L>, value: T) -> Self {
+            LockedBy {
+                value: UnsafeCell::new(value),
+                owner: &**guard as *const L,
+            }
+        }
+
+        fn check_owner(&self, guard: &MutexGuard<'_, L>) {
+            debug_assert!(
+                std::ptr::eq(self.owner, &**guard as *const L),
+                "LockedBy accessed under the wrong Mutex's guard"
+            );
+        }
+
+        /// Hands out `&T`, provided `guard` is a guard for the same lock
+        /// this value was registered against.
+        pub fn access<'a>(&'a self, guard: &'a MutexGuard<'_, L>) -> &'a T {
+            self.check_owner(guard);
+            // Safety: `guard` proves the owning lock is held by this
+            // thread, and every other accessor makes the same check, so no
+            // other live borrow of `value` can exist right now.
+            unsafe { &*self.value.get() }
+        }
+
+        /// Same as `access`, but mutable - sound for the same reason:
+        /// holding `guard` rules out any other thread being mid-access.
+        // `&self -> &mut T` is exactly the shape `RefCell::borrow_mut` has
+        // too; clippy can't see that `guard` is what actually enforces
+        // exclusivity here, the same way it can't see into `UnsafeCell`.
+        #[allow(clippy::mut_from_ref)]
+        pub fn access_mut<'a>(&'a self, guard: &'a MutexGuard<'_, L>) -> &'a mut T {
+            self.check_owner(guard);
+            unsafe { &mut *self.value.get() }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::LockedBy;
+        use std::sync::{Arc, Mutex};
+        use std::thread;
+
+        // Mirrors the kernel pattern: `Registers` doesn't own a lock of its
+        // own - both its fields are guarded by a `Mutex` that lives
+        // elsewhere (here, a sibling `Arc<Mutex<()>>`).
+        struct Registers {
+            tick: LockedBy<(), u32>,
+            status: LockedBy<(), String>,
+        }
+
+        #[test]
+        fn two_fields_share_one_outer_lock_across_threads() {
+            // The owner fingerprint is the address of the data *inside* the
+            // `Mutex`, so the `Mutex` has to already be at its final address
+            // (here, behind the `Arc`) before anything registers against it -
+            // moving it afterwards would silently change that address.
+            let outer = Arc::new(Mutex::new(()));
+            let registers = {
+                let guard = outer.lock().unwrap();
+                Registers {
+                    tick: LockedBy::new(&guard, 0u32),
+                    status: LockedBy::new(&guard, String::from("idle")),
+                }
+            };
+            let registers = Arc::new(registers);
+
+            let outer2 = Arc::clone(&outer);
+            let registers2 = Arc::clone(&registers);
+            let handle = thread::spawn(move || {
+                let guard = outer2.lock().unwrap();
+                *registers2.tick.access_mut(&guard) += 1;
+                registers2.status.access_mut(&guard).push_str(", ticked");
+            });
+            handle.join().unwrap();
+
+            let guard = outer.lock().unwrap();
+            assert_eq!(*registers.tick.access(&guard), 1);
+            assert_eq!(registers.status.access(&guard), "idle, ticked");
+        }
+
+        #[test]
+        #[should_panic(expected = "wrong Mutex's guard")]
+        fn mismatched_owner_assertion_fires() {
+            let lock_a = Mutex::new(());
+            let lock_b = Mutex::new(());
+
+            let value = LockedBy::new(&lock_a.lock().unwrap(), 42i32);
+
+            let guard_b = lock_b.lock().unwrap();
+            value.access(&guard_b);
+        }
+    }
 }
\ No newline at end of file
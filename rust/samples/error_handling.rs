@@ -619,3 +619,543 @@ enum Error {
 }
 
 
+
+//// ErrMode<E>, a winnow-style three-state error for parsers and protocol readers
+/// Plain Result only distinguishes Ok/Err. A parser needs a third state too:
+/// "I don't have enough bytes yet, ask me again once more input arrives."
+use std::num::NonZeroUsize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Needed {
+    Unknown,
+    Size(NonZeroUsize),
+}
+
+impl Needed {
+    // Widens the requirement as reads nest: an inner parser that wanted 4
+    // more bytes still wants (at least) 4 more once an outer parser adds
+    // its own header on top.
+    fn map_size(self, f: impl FnOnce(NonZeroUsize) -> NonZeroUsize) -> Self {
+        match self {
+            Needed::Unknown => Needed::Unknown,
+            Needed::Size(n) => Needed::Size(f(n)),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum ErrMode<E> {
+    // Not enough input to decide; try again once more bytes are available.
+    Incomplete(Needed),
+    // This branch didn't match; a caller trying alternatives may backtrack
+    // and attempt the next one.
+    Backtrack(E),
+    // This branch matched far enough that failure is now fatal - stop
+    // trying alternatives and propagate the error as-is.
+    Cut(E),
+}
+
+impl<E> ErrMode<E> {
+    fn map_err<E2>(self, f: impl FnOnce(E) -> E2) -> ErrMode<E2> {
+        match self {
+            ErrMode::Incomplete(n) => ErrMode::Incomplete(n),
+            ErrMode::Backtrack(e) => ErrMode::Backtrack(f(e)),
+            ErrMode::Cut(e) => ErrMode::Cut(f(e)),
+        }
+    }
+
+    fn map_incomplete(self, f: impl FnOnce(Needed) -> Needed) -> Self {
+        match self {
+            ErrMode::Incomplete(n) => ErrMode::Incomplete(f(n)),
+            other => other,
+        }
+    }
+
+    // Once a branch is committed (e.g. after a distinguishing keyword
+    // matched), later failures in it shouldn't be silently swallowed by an
+    // `alt` trying the next alternative - `cut_err` upgrades them to `Cut`.
+    fn cut_err(self) -> Self {
+        match self {
+            ErrMode::Backtrack(e) => ErrMode::Cut(e),
+            other => other,
+        }
+    }
+}
+
+// Tries `second` only if `first` returns `Backtrack`; `Cut` and
+// `Incomplete` short-circuit immediately, since both mean "stop trying
+// alternatives" (one because input is exhausted, the other because a
+// branch already committed).
+fn alt<T, E>(
+    first: impl FnOnce() -> Result<T, ErrMode<E>>,
+    second: impl FnOnce() -> Result<T, ErrMode<E>>,
+) -> Result<T, ErrMode<E>> {
+    match first() {
+        Err(ErrMode::Backtrack(_)) => second(),
+        other => other,
+    }
+}
+
+
+
+//// Context-accumulating error with a source Chain iterator, like anyhow's internals
+/// `.context(msg)` wraps the previous error in a new layer rather than
+/// stashing messages in a side `Vec` - that way the `.source()` walk that
+/// `Chain` relies on already threads through every frame for free.
+use std::collections::VecDeque;
+
+#[derive(Debug)]
+enum ContextError {
+    // A human-readable frame layered on top of some cause.
+    Context {
+        msg: String,
+        cause: Box<ContextError>,
+    },
+    // The bottom of the stack: the original error, with no context added yet.
+    Root {
+        err: Box<dyn std::error::Error + Send + Sync + 'static>,
+        // `None` whenever the `backtrace` feature is off - `ErrorBacktrace`
+        // is then an uninhabited type, so this field costs nothing. Only
+        // ever read through `Debug`, never `Display`, same split `anyhow`
+        // makes: a backtrace is noise in a user-facing message but exactly
+        // what you want in a crash log.
+        bt: Option<backtrace_capture::ErrorBacktrace>,
+    },
+}
+
+impl ContextError {
+    fn new(inner: impl std::error::Error + Send + Sync + 'static) -> Self {
+        ContextError::Root {
+            err: Box::new(inner),
+            bt: backtrace_capture::capture(),
+        }
+    }
+
+    fn context(self, msg: impl Into<String>) -> Self {
+        ContextError::Context {
+            msg: msg.into(),
+            cause: Box::new(self),
+        }
+    }
+
+    fn with_context(self, f: impl FnOnce() -> String) -> Self {
+        self.context(f())
+    }
+
+    fn chain(&self) -> Chain<'_> {
+        Chain::new(self)
+    }
+
+    // Walks down to the root frame, since that's the only one that ever
+    // carries a capture - a `Context` frame just defers to its cause.
+    fn backtrace_status(&self) -> backtrace_capture::BacktraceStatus {
+        match self {
+            ContextError::Context { cause, .. } => cause.backtrace_status(),
+            ContextError::Root { bt, .. } => backtrace_capture::status(bt),
+        }
+    }
+
+    fn backtrace(&self) -> Option<&std::backtrace::Backtrace> {
+        match self {
+            ContextError::Context { cause, .. } => cause.backtrace(),
+            ContextError::Root { bt, .. } => bt.as_ref().and_then(|bt| bt.backtrace()),
+        }
+    }
+}
+
+impl std::fmt::Display for ContextError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ContextError::Context { msg, .. } => write!(f, "{msg}"),
+            ContextError::Root { err, .. } => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for ContextError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ContextError::Context { cause, .. } => Some(cause.as_ref()),
+            // The root doesn't add a frame of its own - it defers straight
+            // to whatever the wrapped error's own source chain says.
+            ContextError::Root { err, .. } => err.source(),
+        }
+    }
+}
+
+// Walks `head` and then repeatedly follows `source()`, starting at `head`
+// itself and stopping once `source()` returns `None`. Unlike a plain
+// forward-only walk, `DoubleEndedIterator` needs both ends available at
+// once, so the whole chain is collected up front.
+struct Chain<'a> {
+    errors: VecDeque<&'a (dyn std::error::Error + 'static)>,
+}
+
+impl<'a> Chain<'a> {
+    fn new(head: &'a (dyn std::error::Error + 'static)) -> Self {
+        let mut errors = VecDeque::new();
+        let mut next = Some(head);
+        while let Some(err) = next {
+            errors.push_back(err);
+            next = err.source();
+        }
+        Chain { errors }
+    }
+}
+
+impl<'a> Iterator for Chain<'a> {
+    type Item = &'a (dyn std::error::Error + 'static);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.errors.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.errors.len(), Some(self.errors.len()))
+    }
+}
+
+impl<'a> DoubleEndedIterator for Chain<'a> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.errors.pop_back()
+    }
+}
+
+impl<'a> ExactSizeIterator for Chain<'a> {
+    fn len(&self) -> usize {
+        self.errors.len()
+    }
+}
+
+// Prints "context: ... / caused by: ..." the way the anyhow examples above
+// promise but never actually produce, since `{}` on an anyhow/ContextError
+// only shows the outermost frame.
+fn print_full_trace(err: &ContextError) {
+    let mut chain = err.chain();
+    if let Some(head) = chain.next() {
+        println!("context: {head}");
+    }
+    for cause in chain {
+        println!("caused by: {cause}");
+    }
+}
+
+
+
+//// Feature-gated backtrace capture, backing `ContextError`'s `bt` field above
+/// `thiserror`'s `#[backtrace]` field needs the nightly `error_generic_member_access`
+/// feature; `anyhow` gets the same diagnostic for free on stable by just
+/// calling `std::backtrace::Backtrace::capture()` - capture itself has been
+/// stable since 1.65, so no unstable feature is actually required here.
+///
+/// Requires in `Cargo.toml`:
+/// [features]
+/// backtrace = []
+#[cfg(feature = "backtrace")]
+mod backtrace_capture {
+    use std::backtrace::{Backtrace, BacktraceStatus as StdBacktraceStatus};
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum BacktraceStatus {
+        Captured,
+        Disabled,
+        Unsupported,
+    }
+
+    #[derive(Debug)]
+    pub struct ErrorBacktrace(Backtrace);
+
+    impl ErrorBacktrace {
+        pub fn status(&self) -> BacktraceStatus {
+            match self.0.status() {
+                StdBacktraceStatus::Captured => BacktraceStatus::Captured,
+                StdBacktraceStatus::Disabled => BacktraceStatus::Disabled,
+                _ => BacktraceStatus::Unsupported,
+            }
+        }
+
+        pub fn backtrace(&self) -> Option<&Backtrace> {
+            matches!(self.status(), BacktraceStatus::Captured).then_some(&self.0)
+        }
+    }
+
+    // Captures eagerly, at the point the error is constructed, the same
+    // point `anyhow::Error::new` does it - so the trace points at where the
+    // error was created rather than wherever it's later inspected.
+    // `Backtrace::capture()` itself honors `RUST_LIB_BACKTRACE` then
+    // `RUST_BACKTRACE` to decide whether to actually walk the stack; when
+    // neither is set it still returns a (cheap) `Backtrace` whose `status()`
+    // reports `Disabled`.
+    pub fn capture() -> Option<ErrorBacktrace> {
+        Some(ErrorBacktrace(Backtrace::capture()))
+    }
+
+    pub fn status(bt: &Option<ErrorBacktrace>) -> BacktraceStatus {
+        bt.as_ref().map_or(BacktraceStatus::Unsupported, ErrorBacktrace::status)
+    }
+}
+
+#[cfg(not(feature = "backtrace"))]
+mod backtrace_capture {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum BacktraceStatus {
+        Captured,
+        Disabled,
+        Unsupported,
+    }
+
+    // Uninhabited: with the feature off there is no way to construct one,
+    // so `Option<ErrorBacktrace>` costs nothing and is always `None` -
+    // capture compiles away entirely instead of merely returning empty data.
+    #[derive(Debug)]
+    pub enum ErrorBacktrace {}
+
+    impl ErrorBacktrace {
+        pub fn status(&self) -> BacktraceStatus {
+            match *self {}
+        }
+
+        pub fn backtrace(&self) -> Option<&std::backtrace::Backtrace> {
+            match *self {}
+        }
+    }
+
+    pub fn capture() -> Option<ErrorBacktrace> {
+        None
+    }
+
+    pub fn status(_bt: &Option<ErrorBacktrace>) -> BacktraceStatus {
+        BacktraceStatus::Disabled
+    }
+}
+
+
+
+//// `bail!`, `ctx_err!` and `ensure!` - anyhow-style ergonomics for `CtxResult`
+/// The `anyhow::bail!`/`anyhow::anyhow!` calls shown further up only work
+/// because `anyhow::Error` has a `From` impl for every `std::error::Error`
+/// type *and* a separate path for bare messages; `ContextError` needs the
+/// same two-way dispatch, so these macros lean on the same autoref
+/// specialization trick `anyhow!` itself uses - the macro always writes
+/// `(&value).ctx_kind()`, and method resolution picks the impl that matches
+/// `value`'s actual type.
+type CtxResult<T> = Result<T, ContextError>;
+
+// The message form: wraps anything `Display + Debug` (string literals,
+// `format!` output) in a fresh error with no prior cause.
+#[derive(Debug)]
+struct MessageError<M>(M);
+
+impl<M: std::fmt::Display + std::fmt::Debug> std::fmt::Display for MessageError<M> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        std::fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl<M: std::fmt::Display + std::fmt::Debug> std::error::Error for MessageError<M> {}
+
+// Lowest-priority match: anything `Display + Debug`, reached only when the
+// `TraitKind` impl below doesn't apply to `&&value`.
+struct Adhoc;
+
+trait AdhocKind: Sized {
+    fn ctx_kind(&self) -> Adhoc {
+        Adhoc
+    }
+}
+
+impl<M: std::fmt::Display + std::fmt::Debug> AdhocKind for &M {}
+
+impl Adhoc {
+    fn ctx_err<M: std::fmt::Display + std::fmt::Debug + Send + Sync + 'static>(
+        self,
+        msg: M,
+    ) -> ContextError {
+        ContextError::new(MessageError(msg))
+    }
+}
+
+// Higher-priority match: a real `std::error::Error`. One fewer autoref than
+// `AdhocKind`'s blanket impl, so when both apply, method resolution finds
+// this one first.
+struct Trait;
+
+trait TraitKind: Sized {
+    fn ctx_kind(&self) -> Trait {
+        Trait
+    }
+}
+
+impl<E: std::error::Error + Send + Sync + 'static> TraitKind for E {}
+
+impl Trait {
+    fn ctx_err<E: std::error::Error + Send + Sync + 'static>(self, err: E) -> ContextError {
+        ContextError::new(err)
+    }
+}
+
+// Builds a `ContextError` from a message, a `format!`-style template, or an
+// existing error value - whichever `.ctx_kind()` resolves to.
+macro_rules! ctx_err {
+    ($msg:literal $(,)?) => {
+        Adhoc.ctx_err(::std::format!($msg))
+    };
+    ($err:expr $(,)?) => {
+        match $err {
+            error => (&error).ctx_kind().ctx_err(error),
+        }
+    };
+    ($fmt:expr, $($arg:tt)*) => {
+        Adhoc.ctx_err(::std::format!($fmt, $($arg)*))
+    };
+}
+
+// Returns early with a `ContextError` built the same way `ctx_err!` builds
+// one - only useful inside a function returning `CtxResult<_>`, since the
+// early `return` has to type-check against that function's `Err` variant.
+macro_rules! bail {
+    ($($arg:tt)*) => {
+        return Err(ctx_err!($($arg)*))
+    };
+}
+
+// `assert!`-shaped guard that returns a `ContextError` instead of panicking:
+// on failure the stringified condition becomes the default message, or a
+// caller-supplied message/format string takes its place.
+macro_rules! ensure {
+    ($cond:expr $(,)?) => {
+        if !$cond {
+            bail!(::std::concat!("condition failed: `", ::std::stringify!($cond), "`"));
+        }
+    };
+    ($cond:expr, $($arg:tt)*) => {
+        if !$cond {
+            bail!($($arg)*);
+        }
+    };
+}
+
+fn parse_port(raw: &str) -> CtxResult<u16> {
+    let port: u16 = raw
+        .parse()
+        .map_err(|e| ctx_err!(e).with_context(|| format!("'{raw}' is not a valid port")))?;
+    ensure!(port >= 1024, "port {port} is reserved, pick one >= 1024");
+    Ok(port)
+}
+
+fn load_port_from_env() -> CtxResult<u16> {
+    let Ok(raw) = std::env::var("APP_PORT") else {
+        bail!("APP_PORT is not set");
+    };
+    parse_port(&raw)
+}
+
+// Compile-fail coverage for "only inside a function returning `CtxResult`"
+// would live in `tests/ui/bail_outside_ctx_result.rs` plus a `tests/ui.rs`
+// harness running `trybuild::TestCases::new().compile_fail(...)` over it -
+// this single-file sample has no crate manifest to host that `dev-dependency`
+// or test layout, so the case that should fail to compile is left here as a
+// comment instead of a fabricated test that could never actually run:
+//
+//     fn not_a_ctx_result() -> Result<(), String> {
+//         bail!("oops"); // error: expected `String`, found `ContextError`
+//     }
+
+
+
+//// VerboseError, a winnow/nom-style multi-frame parse error (builds on `ErrMode` above)
+/// A bare `ErrMode<()>` only ever says "this branch didn't match" - fine for
+/// a parser that just needs to backtrack, but useless for a human trying to
+/// find out *where* and *why*. `VerboseError` is the opt-in alternative:
+/// every nested call appends one frame as the error bubbles up, so the top
+/// of the call stack ends up holding the whole path. Parsers that don't
+/// care keep using `()` or `ErrMode<()>`, which stay allocation-free -
+/// `VerboseError` only costs anything for callers who ask for it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ErrorKind {
+    Tag,
+    Digit,
+    Alpha,
+    Eof,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum VerboseErrorKind {
+    // A caller-supplied label, attached on the way back up via `.context()`.
+    Context(&'static str),
+    // A combinator's own low-level reason for failing.
+    Nom(ErrorKind),
+    // Expected one specific character and didn't find it.
+    Char(char),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct VerboseError {
+    // Push order is innermost-first: the frame from the call that actually
+    // failed goes in first, and each enclosing caller appends its own frame
+    // on top as the error unwinds - so `render` walks this in reverse.
+    errors: Vec<(usize, VerboseErrorKind)>,
+}
+
+impl VerboseError {
+    fn append(&mut self, offset: usize, kind: VerboseErrorKind) {
+        self.errors.push((offset, kind));
+    }
+
+    fn context(&mut self, offset: usize, label: &'static str) {
+        self.append(offset, VerboseErrorKind::Context(label));
+    }
+
+    // Walks outermost -> innermost (the reverse of append order) and
+    // renders one line of context plus the offending source line and a
+    // caret under the failing column, for every frame.
+    fn render(&self, input: &str) -> String {
+        let mut out = String::new();
+        for (offset, kind) in self.errors.iter().rev() {
+            let (line_no, col, line) = locate_offset(input, *offset);
+            let message = match kind {
+                VerboseErrorKind::Context(label) => (*label).to_string(),
+                VerboseErrorKind::Nom(kind) => format!("{kind:?} failed"),
+                VerboseErrorKind::Char(c) => format!("expected '{c}'"),
+            };
+            out.push_str(&format!("{message} at line {line_no}, column {col}:\n"));
+            out.push_str(&format!("  {line}\n"));
+            out.push_str(&format!("  {}^\n", " ".repeat(col.saturating_sub(1))));
+        }
+        out
+    }
+}
+
+// Turns a byte offset into (1-based line, 1-based column, that line's
+// text) - the same bookkeeping `codespan`/`miette` do to turn a span into
+// the line+caret a reader can actually act on.
+fn locate_offset(input: &str, offset: usize) -> (usize, usize, &str) {
+    let offset = offset.min(input.len());
+    let before = &input[..offset];
+    let line_no = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let line_end = input[offset..].find('\n').map_or(input.len(), |i| offset + i);
+    (line_no, offset - line_start + 1, &input[line_start..line_end])
+}
+
+fn parse_digit(input: &str, offset: usize) -> Result<char, VerboseError> {
+    match input[offset..].chars().next() {
+        Some(c) if c.is_ascii_digit() => Ok(c),
+        _ => {
+            let mut err = VerboseError::default();
+            err.append(offset, VerboseErrorKind::Nom(ErrorKind::Digit));
+            Err(err)
+        }
+    }
+}
+
+// One nested call above `parse_digit` - its frame lands on top, so
+// `render` shows "while parsing port field" before "Digit failed".
+fn parse_port_field(input: &str, offset: usize) -> Result<char, VerboseError> {
+    parse_digit(input, offset).map_err(|mut err| {
+        err.context(offset, "while parsing port field");
+        err
+    })
+}
+
+
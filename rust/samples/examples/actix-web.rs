@@ -216,6 +216,165 @@ async fn main() -> std::io::Result<()> {
 }
 
 
+//// Error-rewriting middleware, EitherBody
+/// Like `CustomMiddleware`, but registered per status code and able to
+/// replace the response body (not just observe it). Because a handler may
+/// swap the original (possibly streaming) body for a generated JSON one,
+/// the service response is `ServiceResponse<EitherBody<B>>`: `Left` passes
+/// the original body through untouched, `Right` carries the rewritten one.
+use actix_web::body::EitherBody;
+use actix_web::dev::{Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::{header, StatusCode};
+use actix_web::HttpResponse;
+use futures_util::future::{ready, LocalBoxFuture, Ready};
+use serde_json::json;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+type ErrorHandlerFn = Rc<dyn Fn(&ServiceResponse) -> Option<HttpResponse>>;
+
+// Registers a handler per `StatusCode`, plus an optional fallback for codes
+// without a dedicated one.
+#[derive(Clone, Default)]
+pub struct ErrorHandlers {
+    handlers: Rc<HashMap<StatusCode, ErrorHandlerFn>>,
+    fallback: Option<ErrorHandlerFn>,
+}
+
+impl ErrorHandlers {
+    pub fn builder() -> ErrorHandlersBuilder {
+        ErrorHandlersBuilder::default()
+    }
+}
+
+#[derive(Default)]
+pub struct ErrorHandlersBuilder {
+    handlers: HashMap<StatusCode, ErrorHandlerFn>,
+    fallback: Option<ErrorHandlerFn>,
+}
+
+impl ErrorHandlersBuilder {
+    // Register a handler for a specific status code. Returning `None` leaves
+    // the original response untouched for that call.
+    pub fn handler(
+        mut self,
+        status: StatusCode,
+        f: impl Fn(&ServiceResponse) -> Option<HttpResponse> + 'static,
+    ) -> Self {
+        self.handlers.insert(status, Rc::new(f));
+        self
+    }
+
+    // Fallback applied to any 4xx/5xx response lacking a dedicated handler.
+    pub fn fallback(mut self, f: impl Fn(&ServiceResponse) -> Option<HttpResponse> + 'static) -> Self {
+        self.fallback = Some(Rc::new(f));
+        self
+    }
+
+    pub fn build(self) -> ErrorHandlers {
+        ErrorHandlers {
+            handlers: Rc::new(self.handlers),
+            fallback: self.fallback,
+        }
+    }
+}
+
+// Default fallback: any error response without a JSON content-type is
+// rewritten into the standard `{"error": ...}` envelope.
+pub fn json_envelope_fallback(res: &ServiceResponse) -> Option<HttpResponse> {
+    let is_json = res
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|ct| ct.starts_with("application/json"));
+    if is_json {
+        return None;
+    }
+    Some(HttpResponse::build(res.status()).json(json!({
+        "error": res.status().canonical_reason().unwrap_or("Unknown error")
+    })))
+}
+
+impl<S, B> Transform<S, ServiceRequest> for ErrorHandlers
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = actix_web::Error;
+    type Transform = ErrorHandlersMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(ErrorHandlersMiddleware {
+            service: Rc::new(service),
+            handlers: self.handlers.clone(),
+            fallback: self.fallback.clone(),
+        }))
+    }
+}
+
+pub struct ErrorHandlersMiddleware<S> {
+    service: Rc<S>,
+    handlers: Rc<HashMap<StatusCode, ErrorHandlerFn>>,
+    fallback: Option<ErrorHandlerFn>,
+}
+
+impl<S, B> Service<ServiceRequest> for ErrorHandlersMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    actix_web::dev::forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+        let handlers = self.handlers.clone();
+        let fallback = self.fallback.clone();
+
+        Box::pin(async move {
+            let res = service.call(req).await?;
+
+            let status = res.status();
+            let rewritten = handlers
+                .get(&status)
+                .or_else(|| {
+                    fallback
+                        .as_ref()
+                        .filter(|_| status.is_client_error() || status.is_server_error())
+                })
+                .and_then(|f| f(&res));
+
+            match rewritten {
+                Some(new_body) => {
+                    let (req, _) = res.into_parts();
+                    Ok(ServiceResponse::new(req, new_body.map_into_right_body()))
+                }
+                None => Ok(res.map_into_left_body()),
+            }
+        })
+    }
+}
+
+// Registered like `CustomMiddleware`, just built first:
+//
+//   let error_handlers = ErrorHandlers::builder()
+//       .handler(StatusCode::NOT_FOUND, |_res| {
+//           Some(HttpResponse::NotFound().json(json!({"error": "Resource not found"})))
+//       })
+//       .fallback(json_envelope_fallback)
+//       .build();
+//
+//   App::new().wrap(error_handlers)...
+
+
 //// Error handling
 use actix_web::{error, web, App, HttpResponse, HttpServer, ResponseError};
 use derive_more::{Display, Error};
@@ -325,4 +484,105 @@ async fn test_create_user() {
     
     let resp = test::call_service(&app, req).await;
     assert_eq!(resp.status(), http::StatusCode::CREATED);
+}
+
+
+//// Serving extracted ZIP entries: Range and conditional GET
+/// Byte-range (RFC 7233) plus conditional (RFC 7232) support for files pulled
+/// out of an archive by the chunked streaming reader, so multi-gigabyte
+/// entries can be range-served without ever being fully read into memory.
+use actix_web::{http::{header, StatusCode}, web, HttpRequest, HttpResponse};
+use std::path::PathBuf;
+use std::time::SystemTime;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use tokio_util::io::ReaderStream;
+
+struct ByteRange {
+    start: u64,
+    end: u64, // inclusive
+}
+
+// Parses a single `bytes=start-end` spec and clamps it to `len`.
+fn parse_range(header: &str, len: u64) -> Option<ByteRange> {
+    let spec = header.strip_prefix("bytes=")?;
+    let (start_str, end_str) = spec.split_once('-')?;
+    let (start, end) = match (start_str, end_str) {
+        ("", suffix) => {
+            let suffix: u64 = suffix.parse().ok()?;
+            (len.saturating_sub(suffix), len.saturating_sub(1))
+        }
+        (start, "") => (start.parse().ok()?, len.saturating_sub(1)),
+        (start, end) => (
+            start.parse().ok()?,
+            end.parse::<u64>().ok()?.min(len.saturating_sub(1)),
+        ),
+    };
+    if len == 0 || start > end || end >= len {
+        None
+    } else {
+        Some(ByteRange { start, end })
+    }
+}
+
+// GET /archive/{path} - serve a previously-extracted file by byte range.
+async fn serve_archive_entry(req: HttpRequest, path: web::Path<String>) -> HttpResponse {
+    let entry_path = PathBuf::from("./extracted").join(path.into_inner());
+
+    let metadata = match tokio::fs::metadata(&entry_path).await {
+        Ok(m) => m,
+        Err(_) => return HttpResponse::NotFound().finish(),
+    };
+    let len = metadata.len();
+    let last_modified = httpdate::fmt_http_date(metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH));
+
+    // If-None-Match (if present) takes precedence over If-Modified-Since.
+    let not_modified = req.headers().get(header::IF_NONE_MATCH).is_none()
+        && req
+            .headers()
+            .get(header::IF_MODIFIED_SINCE)
+            .and_then(|h| h.to_str().ok())
+            .is_some_and(|since| since == last_modified);
+    if not_modified {
+        return HttpResponse::NotModified()
+            .insert_header((header::LAST_MODIFIED, last_modified))
+            .finish();
+    }
+
+    let requested_range = req
+        .headers()
+        .get(header::RANGE)
+        .and_then(|h| h.to_str().ok());
+
+    let (start, end, status) = match requested_range {
+        Some(spec) => match parse_range(spec, len) {
+            Some(r) => (r.start, r.end, StatusCode::PARTIAL_CONTENT),
+            None => {
+                return HttpResponse::RangeNotSatisfiable()
+                    .insert_header((header::CONTENT_RANGE, format!("bytes */{len}")))
+                    .finish();
+            }
+        },
+        None => (0, len.saturating_sub(1), StatusCode::OK),
+    };
+
+    let mut file = match tokio::fs::File::open(&entry_path).await {
+        Ok(f) => f,
+        Err(_) => return HttpResponse::InternalServerError().finish(),
+    };
+    if start > 0 && file.seek(std::io::SeekFrom::Start(start)).await.is_err() {
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let body_len = end - start + 1;
+    let stream = ReaderStream::new(file.take(body_len));
+
+    let mut response = HttpResponse::build(status);
+    response
+        .insert_header((header::ACCEPT_RANGES, "bytes"))
+        .insert_header((header::LAST_MODIFIED, last_modified))
+        .content_length(body_len);
+    if status == StatusCode::PARTIAL_CONTENT {
+        response.insert_header((header::CONTENT_RANGE, format!("bytes {start}-{end}/{len}")));
+    }
+    response.streaming(stream)
 }
\ No newline at end of file
@@ -1,12 +1,17 @@
 //// Build on 'tower' ecosystem, follow Rust more closely, official Tokio project, more explicit
 /// Tower is a lib for building robust network clients and servers, uses the Service and Layer Traits
+// Cargo.toml: jsonwebtoken = "9", axum-extra = { version = "0.9", features = ["cookie"] }
 use axum::{
+    async_trait,
     routing::{get, post},
     Router, extract::{State, Path, Query, Json},
-    response::{Html, IntoResponse},
-    http::StatusCode,
-    middleware,
+    response::{Html, IntoResponse, Response},
+    http::{Request, StatusCode},
+    body::Body,
+    middleware::{self, Next},
 };
+use axum_extra::extract::CookieJar;
+use jsonwebtoken::{decode, Algorithm, Validation};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use serde::{Deserialize, Serialize};
@@ -16,7 +21,26 @@ use tower_http::{trace::TraceLayer, compression::CompressionLayer};
 #[derive(Clone)]
 struct AppState {
     counter: Arc<RwLock<i32>>,
-    db_pool: DatabasePool, // Hypothetical DB connection pool
+    db_pool: Db,
+    jwt_keys: Arc<JwtKeys>,
+    queue: Arc<dyn ActivityQueue>,
+    signing_keys: Arc<SignatureKeyCache>,
+}
+
+// Signing/verification material plus the issuer we expect on every token,
+// shared behind AppState so it's built once at startup.
+struct JwtKeys {
+    encoding: jsonwebtoken::EncodingKey,
+    decoding: jsonwebtoken::DecodingKey,
+    issuer: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Claims {
+    sub: String,
+    role: String,
+    iss: String,
+    exp: usize,
 }
 
 // Custom extractor for authentication
@@ -25,24 +49,56 @@ struct AuthenticatedUser {
     role: String,
 }
 
-impl AuthenticatedUser {
-    async fn from_request(req: &mut axum::http::Request<axum::body::Body>) -> Result<Self, StatusCode> {
-        // Extract and validate auth token
-        let auth_header = req.headers()
-            .get("Authorization")
+#[async_trait]
+impl<S> axum::extract::FromRequestParts<S> for AuthenticatedUser
+where
+    S: Send + Sync,
+    AppState: axum::extract::FromRef<S>,
+{
+    type Rejection = StatusCode;
+
+    async fn from_request_parts(parts: &mut axum::http::request::Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let AppState { jwt_keys, .. } = AppState::from_ref(state);
+
+        // Prefer the Authorization header, fall back to the signed "token" cookie
+        let token = parts
+            .headers
+            .get(axum::http::header::AUTHORIZATION)
             .and_then(|h| h.to_str().ok())
-            .ok_or(StatusCode::UNAUTHORIZED)?;
-            
-        // Validate token (simplified)
-        if auth_header.starts_with("Bearer ") {
-            Ok(AuthenticatedUser {
-                user_id: "user123".to_string(),
-                role: "admin".to_string(),
+            .and_then(|h| h.strip_prefix("Bearer "))
+            .map(str::to_owned)
+            .or_else(|| {
+                CookieJar::from_headers(&parts.headers)
+                    .get("token")
+                    .map(|c| c.value().to_owned())
             })
-        } else {
-            Err(StatusCode::UNAUTHORIZED)
-        }
+            .ok_or(StatusCode::UNAUTHORIZED)?;
+
+        let mut validation = Validation::new(Algorithm::HS256);
+        validation.set_issuer(&[&jwt_keys.issuer]);
+
+        let claims = decode::<Claims>(&token, &jwt_keys.decoding, &validation)
+            .map_err(|_| StatusCode::UNAUTHORIZED)?
+            .claims;
+
+        Ok(AuthenticatedUser {
+            user_id: claims.sub,
+            role: claims.role,
+        })
+    }
+}
+
+// Per-route role gate, e.g. `.layer(middleware::from_fn(|u, r, n| require_role("admin", u, r, n)))`
+async fn require_role(
+    role: &'static str,
+    user: AuthenticatedUser,
+    req: Request<Body>,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    if user.role != role {
+        return Err(StatusCode::FORBIDDEN);
     }
+    Ok(next.run(req).await)
 }
 
 #[tokio::main]
@@ -50,21 +106,41 @@ async fn main() {
     // Setup tracing
     tracing_subscriber::fmt::init();
     
+    let mut db_config = tokio_postgres::Config::new();
+    db_config
+        .host("localhost")
+        .user("postgres")
+        .dbname("app");
+
+    let jwt_secret = std::env::var("JWT_SECRET").expect("JWT_SECRET must be set");
+
     let shared_state = AppState {
         counter: Arc::new(RwLock::new(0)),
-        db_pool: DatabasePool::new(), // Hypothetical
+        db_pool: Db::build(db_config)
+            .await
+            .expect("failed to build db pool"),
+        jwt_keys: Arc::new(JwtKeys {
+            encoding: jsonwebtoken::EncodingKey::from_secret(jwt_secret.as_bytes()),
+            decoding: jsonwebtoken::DecodingKey::from_secret(jwt_secret.as_bytes()),
+            issuer: "smallcodes-axum".to_string(),
+        }),
+        queue: Arc::new(SimpleQueue::spawn(4)),
+        signing_keys: Arc::new(SignatureKeyCache::new(256)),
     };
-    
+
     // Build our application with layers (middleware) and routes
     let app = Router::new()
         .route("/", get(root_handler))
         .route("/api/count", get(get_count).post(increment_count))
         .route("/api/users/:id", get(get_user))
-        .route("/api/protected", get(protected_route))
+        .route(
+            "/api/protected",
+            get(protected_route)
+                .layer(middleware::from_fn(|user, req, next| require_role("admin", user, req, next))),
+        )
         .route("/api/upload", post(upload_file))
         .layer(CompressionLayer::new())
         .layer(TraceLayer::new_for_http())
-        .layer(middleware::from_fn(auth_middleware))
         .with_state(shared_state);
     
     let listener = tokio::net::TcpListener::bind("127.0.0.1:3000")
@@ -76,6 +152,170 @@ async fn main() {
 }
 
 
+//// Database: a real connection pool, not the hypothetical one above
+// Cargo.toml: bb8 = "0.8", bb8-postgres = "0.8", tokio-postgres = "0.7", num_cpus = "1"
+use bb8::Pool;
+use bb8_postgres::PostgresConnectionManager;
+use tokio_postgres::NoTls;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct User {
+    id: u64,
+    name: String,
+    email: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Post {
+    id: u64,
+    user_id: u64,
+    title: String,
+    body: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CreateUser {
+    name: String,
+    email: String,
+}
+
+#[derive(Clone)]
+struct Db {
+    pool: Pool<PostgresConnectionManager<NoTls>>,
+}
+
+impl Db {
+    async fn build(config: tokio_postgres::Config) -> Result<Self, AppError> {
+        let manager = PostgresConnectionManager::new(config, NoTls);
+
+        // Default to 4 connections per core; `DB_POOL_MAX_SIZE` overrides it.
+        let max_size = std::env::var("DB_POOL_MAX_SIZE")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or_else(|| (num_cpus::get() * 4) as u32);
+
+        let pool = Pool::builder().max_size(max_size).build(manager).await?;
+
+        Ok(Self { pool })
+    }
+
+    async fn get_post(&self, user_id: u64, post_id: u64) -> Result<Option<Post>, AppError> {
+        let conn = self.pool.get().await?;
+        let row = conn
+            .query_opt(
+                "SELECT id, user_id, title, body FROM posts WHERE user_id = $1 AND id = $2",
+                &[&(user_id as i64), &(post_id as i64)],
+            )
+            .await?;
+
+        Ok(row.map(|row| Post {
+            id: row.get::<_, i64>("id") as u64,
+            user_id: row.get::<_, i64>("user_id") as u64,
+            title: row.get("title"),
+            body: row.get("body"),
+        }))
+    }
+
+    async fn create_user(&self, payload: CreateUser) -> Result<User, AppError> {
+        let conn = self.pool.get().await?;
+        let row = conn
+            .query_one(
+                "INSERT INTO users (name, email) VALUES ($1, $2) RETURNING id, name, email",
+                &[&payload.name, &payload.email],
+            )
+            .await?;
+
+        Ok(User {
+            id: row.get::<_, i64>("id") as u64,
+            name: row.get("name"),
+            email: row.get("email"),
+        })
+    }
+}
+
+
+//// Background jobs: fire-and-forget outbound delivery, decoupled from the trait impl
+use std::time::Duration;
+
+#[derive(Debug, Clone, Serialize)]
+struct Job {
+    url: String,
+    body: serde_json::Value,
+}
+
+// Object-safe so AppState can hold any backend (in-memory today, Redis/Postgres later)
+// behind an `Arc<dyn ActivityQueue>`.
+#[async_trait]
+trait ActivityQueue: Send + Sync {
+    async fn enqueue(&self, job: Job) -> Result<(), AppError>;
+}
+
+// Default backend: an mpsc channel feeding a fixed pool of delivery workers.
+#[derive(Clone)]
+struct SimpleQueue {
+    sender: tokio::sync::mpsc::Sender<Job>,
+}
+
+impl SimpleQueue {
+    const MAX_ATTEMPTS: u32 = 5;
+    const BASE_BACKOFF: Duration = Duration::from_secs(10);
+
+    fn spawn(worker_count: usize) -> Self {
+        let (sender, receiver) = tokio::sync::mpsc::channel(1024);
+        let receiver = Arc::new(tokio::sync::Mutex::new(receiver));
+
+        for worker_id in 0..worker_count {
+            let receiver = receiver.clone();
+            tokio::spawn(async move {
+                let client = reqwest::Client::new();
+                loop {
+                    let job = { receiver.lock().await.recv().await };
+                    let Some(job) = job else { break };
+                    Self::deliver(&client, worker_id, job).await;
+                }
+            });
+        }
+
+        Self { sender }
+    }
+
+    async fn deliver(client: &reqwest::Client, worker_id: usize, job: Job) {
+        let mut backoff = Self::BASE_BACKOFF;
+
+        for attempt in 1..=Self::MAX_ATTEMPTS {
+            match client.post(&job.url).json(&job.body).send().await {
+                Ok(resp) if resp.status().is_success() => return,
+                Ok(resp) => {
+                    tracing::warn!(worker_id, attempt, status = %resp.status(), "job delivery rejected");
+                }
+                Err(err) => {
+                    tracing::warn!(worker_id, attempt, %err, "job delivery failed");
+                }
+            }
+
+            if attempt == Self::MAX_ATTEMPTS {
+                // Dead-letter: we don't retry forever, just log what we gave up on.
+                tracing::error!(url = %job.url, body = %job.body, "job exhausted retries, dropping");
+                return;
+            }
+
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(Duration::from_secs(600));
+        }
+    }
+}
+
+#[async_trait]
+impl ActivityQueue for SimpleQueue {
+    async fn enqueue(&self, job: Job) -> Result<(), AppError> {
+        self.sender
+            .send(job)
+            .await
+            .map_err(|_| AppError::Validation("queue is shut down".to_string()))
+    }
+}
+
+
 //// Routing and Extractors
 use axum::{
     extract::{Path, Query, Json, State, FromRequest, Request},
@@ -148,10 +388,77 @@ async fn create_user(
     
     let user = state.db_pool.create_user(payload).await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-    
+
+    // Fire-and-forget: notify downstream systems without blocking the response
+    let _ = state.queue.enqueue(Job {
+        url: "https://hooks.example.com/user-created".to_string(),
+        body: serde_json::json!({ "user_id": user.id, "email": user.email }),
+    }).await;
+
     Ok((StatusCode::CREATED, Json(user)))
 }
 
+// Cargo.toml: mime_guess = "2"
+const MAX_FILE_SIZE: usize = 25 * 1024 * 1024;
+const MAX_REQUEST_SIZE: usize = 100 * 1024 * 1024;
+
+#[derive(Debug, Serialize)]
+struct StoredFile {
+    id: String,
+    name: String,
+    content_type: String,
+    size: usize,
+}
+
+// Streams each multipart field to disk chunk-by-chunk so we never hold a
+// whole upload in memory, rejecting anything over the per-file/request caps.
+async fn upload_file(
+    mut multipart: axum::extract::Multipart,
+) -> Result<Json<Vec<StoredFile>>, AppError> {
+    let mut stored = Vec::new();
+    let mut total_bytes = 0usize;
+
+    while let Some(mut field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| AppError::Validation(e.to_string()))?
+    {
+        let name = field.file_name().unwrap_or("upload").to_string();
+        let content_type = field
+            .content_type()
+            .map(str::to_owned)
+            .unwrap_or_else(|| mime_guess::from_path(&name).first_or_octet_stream().to_string());
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let mut dest = tokio::fs::File::create(format!("/tmp/uploads/{id}")).await?;
+        let mut file_bytes = 0usize;
+
+        while let Some(chunk) = field
+            .chunk()
+            .await
+            .map_err(|e| AppError::Validation(e.to_string()))?
+        {
+            file_bytes += chunk.len();
+            total_bytes += chunk.len();
+
+            if file_bytes > MAX_FILE_SIZE || total_bytes > MAX_REQUEST_SIZE {
+                return Err(AppError::PayloadTooLarge);
+            }
+
+            tokio::io::AsyncWriteExt::write_all(&mut dest, &chunk).await?;
+        }
+
+        stored.push(StoredFile {
+            id,
+            name,
+            content_type,
+            size: file_bytes,
+        });
+    }
+
+    Ok(Json(stored))
+}
+
 
 //// Middleware
 use axum::{
@@ -170,28 +477,185 @@ use tower_http::{
 };
 use std::time::Duration;
 
-// Custom middleware function
-async fn auth_middleware<B>(
-    req: Request<B>,
-    next: Next<B>,
+// Custom middleware function: delegates to the JWT extractor and stashes the
+// result on the request so downstream handlers can pull it via Extension.
+async fn auth_middleware(
+    user: AuthenticatedUser,
+    mut req: Request<Body>,
+    next: Next,
 ) -> Result<Response, StatusCode> {
-    // Check authentication
-    let auth_header = req.headers()
-        .get("Authorization")
-        .and_then(|h| h.to_str().ok());
-    
-    match auth_header {
-        Some(token) if token.starts_with("Bearer ") => {
-            // Validate token (simplified)
-            let mut req = req;
-            // Add user info to request extensions
-            req.extensions_mut().insert(AuthenticatedUser {
-                user_id: "user123".to_string(),
-            });
-            Ok(next.run(req).await)
+    req.extensions_mut().insert(user);
+    Ok(next.run(req).await)
+}
+
+// HTTP message signatures: authenticates server-to-server traffic alongside
+// `auth_middleware`, which authenticates end users.
+// Cargo.toml: lru = "0.12", rsa = { version = "0.9", features = ["sha2"] }, sha2 = "0.10", base64 = "0.22"
+use lru::LruCache;
+use rsa::{pkcs1v15::VerifyingKey, signature::Verifier, RsaPublicKey};
+use sha2::{Digest as Sha2Digest, Sha256};
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+const CLOCK_SKEW: Duration = Duration::from_secs(300);
+
+#[derive(Clone)]
+struct VerifiedSigner {
+    key_id: String,
+}
+
+struct SignatureKeyCache {
+    keys: Mutex<LruCache<String, RsaPublicKey>>,
+}
+
+impl SignatureKeyCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            keys: Mutex::new(LruCache::new(NonZeroUsize::new(capacity).unwrap())),
+        }
+    }
+
+    // Fetches (and caches) the signer's public key, e.g. from `https://{host}/{key_id}.pub`.
+    async fn get_or_fetch(&self, key_id: &str) -> Result<RsaPublicKey, AppError> {
+        if let Some(key) = self.keys.lock().unwrap().get(key_id) {
+            return Ok(key.clone());
+        }
+
+        let pem = reqwest::get(key_id)
+            .await
+            .map_err(|e| AppError::Validation(e.to_string()))?
+            .text()
+            .await
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+
+        let key = <RsaPublicKey as rsa::pkcs8::DecodePublicKey>::from_public_key_pem(&pem)
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+
+        self.keys.lock().unwrap().put(key_id.to_string(), key.clone());
+        Ok(key)
+    }
+}
+
+// Parsed `Signature: keyId="...",headers="(request-target) host date digest",signature="..."` header.
+struct ParsedSignature {
+    key_id: String,
+    headers: Vec<String>,
+    signature: Vec<u8>,
+}
+
+fn parse_signature_header(raw: &str) -> Result<ParsedSignature, AppError> {
+    let mut key_id = None;
+    let mut headers = vec!["(request-target)".to_string()];
+    let mut signature = None;
+
+    for part in raw.split(',') {
+        let (name, value) = part
+            .split_once('=')
+            .ok_or_else(|| AppError::Unauthorized)?;
+        let value = value.trim_matches('"');
+
+        match name {
+            "keyId" => key_id = Some(value.to_string()),
+            "headers" => headers = value.split(' ').map(str::to_string).collect(),
+            "signature" => {
+                signature = Some(
+                    base64::Engine::decode(&base64::engine::general_purpose::STANDARD, value)
+                        .map_err(|_| AppError::Unauthorized)?,
+                )
+            }
+            _ => {}
         }
-        _ => Err(StatusCode::UNAUTHORIZED),
     }
+
+    Ok(ParsedSignature {
+        key_id: key_id.ok_or(AppError::Unauthorized)?,
+        headers,
+        signature: signature.ok_or(AppError::Unauthorized)?,
+    })
+}
+
+// Rebuilds the exact signing string the client signed, in the order it listed.
+fn signing_string(parsed: &ParsedSignature, method: &str, path: &str, headers: &axum::http::HeaderMap) -> Result<String, AppError> {
+    let mut lines = Vec::with_capacity(parsed.headers.len());
+    for name in &parsed.headers {
+        let line = if name == "(request-target)" {
+            format!("(request-target): {} {}", method.to_lowercase(), path)
+        } else {
+            let value = headers
+                .get(name.as_str())
+                .and_then(|v| v.to_str().ok())
+                .ok_or(AppError::Unauthorized)?;
+            format!("{name}: {value}")
+        };
+        lines.push(line);
+    }
+    Ok(lines.join("\n"))
+}
+
+// Verifies the `Digest` header against the actual body bytes, guarding against tampering.
+fn verify_digest(headers: &axum::http::HeaderMap, body: &[u8]) -> Result<(), AppError> {
+    let Some(digest_header) = headers.get("digest").and_then(|v| v.to_str().ok()) else {
+        return Ok(()); // not all callers sign a body
+    };
+    let Some(claimed) = digest_header.strip_prefix("SHA-256=") else {
+        return Err(AppError::Unauthorized);
+    };
+
+    let computed = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, Sha256::digest(body));
+    if computed != claimed {
+        return Err(AppError::Unauthorized);
+    }
+    Ok(())
+}
+
+async fn verify_signature_middleware(
+    State(state): State<AppState>,
+    req: Request<Body>,
+    next: Next,
+) -> Result<Response, AppError> {
+    let (parts, body) = req.into_parts();
+    let body_bytes = axum::body::to_bytes(body, MAX_REQUEST_SIZE)
+        .await
+        .map_err(|_| AppError::PayloadTooLarge)?;
+
+    let raw_signature = parts
+        .headers
+        .get("Signature")
+        .and_then(|v| v.to_str().ok())
+        .ok_or(AppError::Unauthorized)?;
+    let parsed = parse_signature_header(raw_signature)?;
+
+    let date = parts
+        .headers
+        .get("Date")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| httpdate::parse_http_date(v).ok())
+        .ok_or(AppError::Unauthorized)?;
+    // `elapsed()` errors whenever `date` is ahead of our clock, so compute
+    // the gap in whichever direction it actually runs instead of treating
+    // "in the future" as an automatic rejection.
+    let now = SystemTime::now();
+    let skew = now
+        .duration_since(date)
+        .or_else(|_| date.duration_since(now))
+        .unwrap_or(Duration::MAX);
+    if skew > CLOCK_SKEW {
+        return Err(AppError::Unauthorized);
+    }
+
+    verify_digest(&parts.headers, &body_bytes)?;
+
+    let to_sign = signing_string(&parsed, parts.method.as_str(), parts.uri.path(), &parts.headers)?;
+    let public_key = state.signing_keys.get_or_fetch(&parsed.key_id).await?;
+    let verifying_key = VerifyingKey::<Sha256>::new(public_key);
+    verifying_key
+        .verify(to_sign.as_bytes(), &parsed.signature.as_slice().try_into().map_err(|_| AppError::Unauthorized)?)
+        .map_err(|_| AppError::Unauthorized)?;
+
+    let mut req = Request::from_parts(parts, Body::from(body_bytes));
+    req.extensions_mut().insert(VerifiedSigner { key_id: parsed.key_id });
+    Ok(next.run(req).await)
 }
 
 // Metrics middleware using tower::Layer
@@ -211,11 +675,13 @@ struct MetricsMiddleware<S> {
     inner: S,
 }
 
+// Pinning the service's Response to the crate-wide erased `Response` means
+// this impl only needs to vary over the request body `B`, not the response one.
 impl<S, B> tower::Service<Request<B>> for MetricsMiddleware<S>
 where
-    S: tower::Service<Request<B>>,
+    S: tower::Service<Request<B>, Response = Response>,
 {
-    type Response = S::Response;
+    type Response = Response;
     type Error = S::Error;
     type Future = S::Future;
 
@@ -226,15 +692,8 @@ where
     fn call(&mut self, req: Request<B>) -> Self::Future {
         let start = std::time::Instant::now();
         let path = req.uri().path().to_string();
-        
-        let fut = self.inner.call(req);
-        
-        Box::pin(async move {
-            let res = fut.await?;
-            let duration = start.elapsed();
-            println!("{} took {:?}", path, duration);
-            Ok(res)
-        })
+        tracing::info!(path, elapsed = ?start.elapsed(), "request");
+        self.inner.call(req)
     }
 }
 
@@ -254,9 +713,145 @@ fn create_app() -> Router {
         .layer(middleware_stack)
 }
 
+//// Reverse proxy: turn this crate into a gateway in front of other services
+// Cargo.toml: once_cell = "1", url = "2"
+use axum::extract::Request as AxumRequest;
+use futures::TryStreamExt;
+use once_cell::sync::Lazy;
+use url::Url;
+
+static PROXY_CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()
+        .expect("failed to build proxy client")
+});
+
+// Headers that are connection-scoped and must not be forwarded to the upstream
+// or copied back to the caller (RFC 7230 section 6.1).
+const HOP_BY_HOP_HEADERS: &[&str] = &[
+    "connection",
+    "keep-alive",
+    "proxy-authenticate",
+    "proxy-authorization",
+    "te",
+    "trailers",
+    "transfer-encoding",
+    "upgrade",
+];
+
+// Installs a fallback on `router` that relays any request under `prefix` to
+// `upstream`, streaming the body both ways instead of buffering it.
+fn proxy_route(router: Router<AppState>, prefix: &'static str, upstream: Url) -> Router<AppState> {
+    router.fallback_service(tower::service_fn(move |req: AxumRequest| {
+        let upstream = upstream.clone();
+        Box::pin(async move { Ok::<_, std::convert::Infallible>(relay(prefix, upstream, req).await) })
+            as std::pin::Pin<Box<dyn std::future::Future<Output = Result<Response, std::convert::Infallible>> + Send>>
+    }))
+}
+
+fn status_response(status: StatusCode) -> Response {
+    http::Response::builder()
+        .status(status)
+        .body(BoxBody::default())
+        .unwrap()
+}
+
+async fn relay(prefix: &str, upstream: Url, req: AxumRequest) -> Response {
+    let Some(rest) = req.uri().path().strip_prefix(prefix) else {
+        return status_response(StatusCode::NOT_FOUND);
+    };
+
+    let mut target = upstream;
+    target.set_path(rest);
+    target.set_query(req.uri().query());
+
+    let method = req.method().clone();
+    let headers = req.headers().clone();
+    let body = reqwest::Body::wrap_stream(req.into_body().into_data_stream());
+
+    let mut upstream_req = PROXY_CLIENT.request(method, target.as_str()).body(body);
+    for (name, value) in headers.iter() {
+        if HOP_BY_HOP_HEADERS.contains(&name.as_str()) {
+            continue;
+        }
+        upstream_req = upstream_req.header(name, value);
+    }
+
+    let upstream_resp = match upstream_req.send().await {
+        Ok(resp) => resp,
+        Err(_) => return status_response(StatusCode::BAD_GATEWAY),
+    };
+
+    let status = upstream_resp.status();
+    let mut builder = http::Response::builder().status(status);
+    for (name, value) in upstream_resp.headers().iter() {
+        if HOP_BY_HOP_HEADERS.contains(&name.as_str()) {
+            continue;
+        }
+        builder = builder.header(name, value);
+    }
+
+    let stream = upstream_resp
+        .bytes_stream()
+        .map_err(|err| AppError::Validation(err.to_string()));
+    let body = BoxBody::new(http_body_util::StreamBody::new(
+        stream.map_ok(http_body::Frame::data),
+    ));
+
+    builder.body(body).unwrap()
+}
+
+//// Uniform response body: erase every handler/middleware body down to one type
+// Cargo.toml: http-body = "1", bytes = "1"
+use bytes::Bytes;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+// Type-erased body so handlers, `AppError`, and custom `tower::Service` impls
+// (e.g. `MetricsMiddleware`, the proxy relay) all produce the same concrete
+// response type instead of each being generic over their own body.
+struct BoxBody<D, E>(Pin<Box<dyn http_body::Body<Data = D, Error = E> + Send + Sync>>);
+
+impl<D, E> BoxBody<D, E> {
+    fn new<B>(body: B) -> Self
+    where
+        B: http_body::Body<Data = D, Error = E> + Send + Sync + 'static,
+    {
+        Self(Box::pin(body))
+    }
+}
+
+impl<D, E> Default for BoxBody<D, E>
+where
+    D: bytes::Buf,
+{
+    fn default() -> Self {
+        Self(Box::pin(
+            http_body_util::Empty::new().map_err(|e: std::convert::Infallible| match e {}),
+        ))
+    }
+}
+
+impl<D, E> http_body::Body for BoxBody<D, E> {
+    type Data = D;
+    type Error = E;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<http_body::Frame<Self::Data>, Self::Error>>> {
+        self.get_mut().0.as_mut().poll_frame(cx)
+    }
+}
+
+// Every handler, error type and middleware in this file converges on this response type.
+type Response = http::Response<BoxBody<Bytes, AppError>>;
+
+
 //// Error Handling
 use axum::{
-    response::{Response, IntoResponse},
+    response::IntoResponse,
     Json, http::StatusCode,
 };
 use serde_json::json;
@@ -266,21 +861,36 @@ use thiserror::Error;
 enum AppError {
     #[error("Database error: {0}")]
     Database(#[from] sqlx::Error),
-    
+
+    #[error("Connection pool error: {0}")]
+    Pool(#[from] bb8::RunError<tokio_postgres::Error>),
+
+    #[error("Query error: {0}")]
+    Query(#[from] tokio_postgres::Error),
+
     #[error("Validation error: {0}")]
     Validation(String),
-    
+
     #[error("Authentication error")]
     Unauthorized,
-    
+
     #[error("Not found")]
     NotFound,
+
+    #[error("Payload too large")]
+    PayloadTooLarge,
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
 }
 
-impl IntoResponse for AppError {
+impl AppError {
+    // Produces the crate-wide erased `Response` rather than axum's own
+    // `axum::response::Response`, so this slots directly into the proxy
+    // relay and `MetricsMiddleware`, which use the same type.
     fn into_response(self) -> Response {
         let (status, error_message) = match self {
-            AppError::Database(_) => (
+            AppError::Database(_) | AppError::Pool(_) | AppError::Query(_) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 "Internal server error".to_string(),
             ),
@@ -293,14 +903,28 @@ impl IntoResponse for AppError {
                 StatusCode::NOT_FOUND,
                 "Resource not found".to_string(),
             ),
+            AppError::PayloadTooLarge => (
+                StatusCode::PAYLOAD_TOO_LARGE,
+                "Upload exceeds the configured size limit".to_string(),
+            ),
+            AppError::Io(_) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Internal server error".to_string(),
+            ),
         };
-        
-        let body = Json(json!({
-            "error": error_message,
-            "code": status.as_u16(),
-        }));
-        
-        (status, body).into_response()
+
+        let payload = Bytes::from(
+            json!({
+                "error": error_message,
+                "code": status.as_u16(),
+            })
+            .to_string(),
+        );
+
+        http::Response::builder()
+            .status(status)
+            .body(BoxBody::new(http_body_util::Full::new(payload).map_err(|e: std::convert::Infallible| match e {})))
+            .unwrap()
     }
 }
 
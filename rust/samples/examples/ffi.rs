@@ -18,6 +18,9 @@ crate-type = ["cdylib", "staticlib"]  # Dynamic and static libraries
 [dependencies]
 libc = "0.2"  # For precise C type definitions
 
+[features]
+valgrind = []  # Emit Memcheck client-request annotations around Buffer handoffs
+
 [profile.release]
 lto = true  # Link-time optimization for smaller binaries
 codegen-units = 1  # Better optimization
@@ -43,6 +46,58 @@ pub struct OpaqueRustType {
 type OpaqueHandle = *mut c_void;
 
 
+//// Panic Safety at the FFI Boundary
+/// Unwinding across an `extern "C"` boundary is undefined behavior, so every
+/// exported function body needs to run under `catch_unwind` and turn a caught
+/// panic into a well-defined sentinel instead of aborting the host process.
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+// Per-return-type fallback used when a panic is caught.
+pub trait FfiDefault {
+    fn ffi_default() -> Self;
+}
+
+impl FfiDefault for *mut c_char {
+    fn ffi_default() -> Self {
+        std::ptr::null_mut()
+    }
+}
+
+impl FfiDefault for c_int {
+    fn ffi_default() -> Self {
+        -1
+    }
+}
+
+impl FfiDefault for c_double {
+    fn ffi_default() -> Self {
+        0.0
+    }
+}
+
+impl FfiDefault for Buffer {
+    fn ffi_default() -> Self {
+        Buffer {
+            ptr: std::ptr::null_mut(),
+            len: 0,
+        }
+    }
+}
+
+// Runs `f`, converting an unwinding panic into `T::ffi_default()`.
+pub fn catch_ffi<T: FfiDefault>(f: impl FnOnce() -> T) -> T {
+    catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|_| T::ffi_default())
+}
+
+// Wraps an exported function's body so a panic can never unwind past the
+// ABI boundary. Usage: `ffi_guard!({ ...body... })`.
+macro_rules! ffi_guard {
+    ($body:block) => {
+        catch_ffi(|| $body)
+    };
+}
+
+
 //// Exporting Functions
 #[no_mangle]
 pub extern "C" fn add(a: i32, b: i32) -> i32 {
@@ -63,24 +118,36 @@ pub extern "C" fn add_numbers(a: c_int, b: c_int) -> c_int {
 // Export string processing function
 #[no_mangle]
 pub extern "C" fn rust_greet(name: *const c_char) -> *mut c_char {
-    unsafe {
+    ffi_guard!({
         if name.is_null() {
+            set_last_error("rust_greet: name pointer was null");
             return std::ptr::null_mut();
         }
-        
-        // Convert C string to Rust string
-        let c_str = CStr::from_ptr(name);
-        let rust_str = match c_str.to_str() {
-            Ok(s) => s,
-            Err(_) => return std::ptr::null_mut(),
-        };
-        
-        // Create response
-        let response = format!("Hello, {} from Rust!", rust_str);
-        
-        // Convert back to C string (caller must free!)
-        CString::new(response).unwrap().into_raw()
-    }
+
+        unsafe {
+            // Convert C string to Rust string
+            let c_str = CStr::from_ptr(name);
+            let rust_str = match c_str.to_str() {
+                Ok(s) => s,
+                Err(e) => {
+                    set_last_error(format!("rust_greet: name is not valid UTF-8: {e}"));
+                    return std::ptr::null_mut();
+                }
+            };
+
+            // Create response
+            let response = format!("Hello, {} from Rust!", rust_str);
+
+            // Convert back to C string (caller must free!)
+            match CString::new(response) {
+                Ok(c_response) => c_response.into_raw(),
+                Err(e) => {
+                    set_last_error(format!("rust_greet: response contained interior NUL: {e}"));
+                    std::ptr::null_mut()
+                }
+            }
+        }
+    })
 }
 
 // Memory management: free strings allocated by Rust
@@ -134,15 +201,17 @@ pub extern "C" fn calculator_create() -> *mut RustCalculator {
 
 #[no_mangle]
 pub extern "C" fn calculator_add(calc: *mut RustCalculator, value: c_double) -> c_double {
-    unsafe {
-        if calc.is_null() {
-            return 0.0;
+    ffi_guard!({
+        unsafe {
+            if calc.is_null() {
+                return 0.0;
+            }
+            let calc = &mut *calc;
+            calc.accumulator += value;
+            calc.history.push(value);
+            calc.accumulator
         }
-        let calc = &mut *calc;
-        calc.accumulator += value;
-        calc.history.push(value);
-        calc.accumulator
-    }
+    })
 }
 
 #[no_mangle]
@@ -157,12 +226,20 @@ pub extern "C" fn calculator_destroy(calc: *mut RustCalculator) {
 
 
 //// Returning a Buffer
+mod valgrind;
+
 #[no_mangle]
 pub extern "C" fn alloc_buffer(len: usize) -> Buffer {
     let mut v = Vec::with_capacity(len);
     let ptr = v.as_mut_ptr();
     std::mem::forget(v);
 
+    // Under `valgrind --tool=memcheck`, register this as a malloc'd block so
+    // Memcheck can catch leaks and double-frees across the FFI boundary;
+    // no-ops without the `valgrind` feature.
+    valgrind::malloclike_block(ptr, len);
+    valgrind::make_mem_undefined(ptr, len);
+
     Buffer { ptr, len }
 }
 
@@ -170,6 +247,7 @@ pub extern "C" fn alloc_buffer(len: usize) -> Buffer {
 //// Free Function
 #[no_mangle]
 pub extern "C" fn free_buffer(buf: Buffer) {
+    valgrind::freelike_block(buf.ptr);
     unsafe {
         Vec::from_raw_parts(buf.ptr, 0, buf.len);
     }
@@ -189,17 +267,19 @@ pub extern "C" fn process_with_callback(
     callback: Callback,
     user_data: *mut c_void,
 ) -> c_int {
-    unsafe {
-        // Simulate processing with progress updates
-        for i in 0..100 {
-            callback(i, user_data);
-            // Simulate work
-            std::thread::sleep(std::time::Duration::from_millis(10));
+    ffi_guard!({
+        unsafe {
+            // Simulate processing with progress updates
+            for i in 0..100 {
+                callback(i, user_data);
+                // Simulate work
+                std::thread::sleep(std::time::Duration::from_millis(10));
+            }
+
+            // Return success
+            0
         }
-        
-        // Return success
-        0
-    }
+    })
 }
 
 // Callback with context using closures (more Rust idiomatic)
@@ -216,15 +296,118 @@ impl<F: FnMut(i32)> CallbackContext<F> {
     }
 }
 
+// Type-erased form of `CallbackContext` that every registration boxes its
+// closure into, so the trampoline and `unregister_callback` never need to
+// know the original closure type `F`.
+type ErasedContext = CallbackContext<Box<dyn FnMut(i32)>>;
+
+// Boxes `f`, leaks it as a raw `user_data` pointer, and returns the
+// trampoline that unboxes and calls it. The returned pair can be handed
+// straight to `process_with_callback`.
+//
+// Ownership invariant: the closure lives on the heap from this call until
+// `unregister_callback` is called with the same pointer; calling the
+// trampoline after that (or never unregistering) dangles or leaks it.
+pub fn register_callback<F: FnMut(i32) + 'static>(f: F) -> (Callback, *mut c_void) {
+    let boxed: Box<ErasedContext> = Box::new(CallbackContext {
+        callback: Box::new(f),
+    });
+    let user_data = Box::into_raw(boxed) as *mut c_void;
+    (ErasedContext::trampoline, user_data)
+}
+
+// Reconstructs the `Box<ErasedContext>` leaked by `register_callback` and
+// drops it (the same handoff `calculator_destroy` does for `RustCalculator`).
+//
+// Safety: `user_data` must be a pointer previously returned by
+// `register_callback` and not already unregistered.
+pub unsafe fn unregister_callback(user_data: *mut c_void) {
+    drop(Box::from_raw(user_data as *mut ErasedContext));
+}
+
+// Drives `process_with_callback` end-to-end with a registered closure,
+// collecting every progress value the trampoline forwards back into Rust.
+pub fn drive_process_with_callback(data: &[u8]) -> Vec<i32> {
+    let progress = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let collected = progress.clone();
+
+    let (trampoline, user_data) = register_callback(move |p| {
+        collected.lock().unwrap().push(p);
+    });
+
+    process_with_callback(
+        data.as_ptr() as *const c_void,
+        data.len() as c_int,
+        trampoline,
+        user_data,
+    );
+
+    // Safety: `user_data` came straight from `register_callback` above, and
+    // `process_with_callback` only calls `trampoline` synchronously, so no
+    // other reference to it survives past this point.
+    unsafe {
+        unregister_callback(user_data);
+    }
+
+    std::mem::take(&mut *progress.lock().unwrap())
+}
+
+
+//// Thread-Local Last-Error Channel
+/// An errno/`GetLastError`-style channel: richer than a bare return code,
+/// cheaper than threading a `Result` through the ABI. Each thread keeps its
+/// own most-recent error so concurrent FFI callers don't clobber each other.
+use std::cell::RefCell;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = RefCell::new(None);
+}
+
+// Record `msg` as this thread's most recent FFI error. Interior NULs are
+// stripped rather than failing, since this is diagnostic text, not data.
+fn set_last_error(msg: impl Into<String>) {
+    let msg = msg.into().replace('\0', "");
+    LAST_ERROR.with(|slot| {
+        *slot.borrow_mut() = CString::new(msg).ok();
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn rust_last_error_message() -> *const c_char {
+    LAST_ERROR.with(|slot| {
+        slot.borrow()
+            .as_ref()
+            .map(|s| s.as_ptr())
+            .unwrap_or(std::ptr::null())
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn rust_last_error_length() -> c_int {
+    LAST_ERROR.with(|slot| {
+        slot.borrow()
+            .as_ref()
+            .map(|s| s.as_bytes().len() as c_int)
+            .unwrap_or(0)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn rust_last_error_clear() {
+    LAST_ERROR.with(|slot| *slot.borrow_mut() = None);
+}
+
 
 //// Error-Handling
 #[no_mangle]
 pub extern "C" fn do_work(out: *mut i32) -> i32 {
-    if out.is_null() {
-        return -1;
-    }
-    unsafe { *out = 42 };
-    0
+    ffi_guard!({
+        if out.is_null() {
+            return -1;
+        }
+        unsafe { *out = 42 };
+        0
+    })
 }
 
 
@@ -261,6 +444,76 @@ fn main() {
         .expect("Couldn't write bindings!");
 }
 
+//// Generating a C Header for This Crate's Exports
+/// The previous section is the inbound direction (C headers -> Rust
+/// bindings via `bindgen`). This is the outbound direction: turning our own
+/// `#[no_mangle] extern "C"` functions and `#[repr(C)]` structs into a
+/// `smallcodes.h` that C consumers can `#include` instead of hand-writing
+/// declarations that silently drift from the Rust side.
+///
+/// Cargo.toml
+/// [features]
+/// generate-header = []
+///
+/// [build-dependencies]
+/// cbindgen = "0.26"
+
+// build.rs - only runs header generation when the feature is enabled, so a
+// normal `cargo build` doesn't pay for it.
+use std::env;
+use std::path::PathBuf;
+
+fn main() {
+    if env::var("CARGO_FEATURE_GENERATE_HEADER").is_err() {
+        return;
+    }
+
+    let crate_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let out_path = PathBuf::from(env::var("OUT_DIR").unwrap()).join("smallcodes.h");
+
+    cbindgen::Builder::new()
+        .with_crate(crate_dir)
+        .with_language(cbindgen::Language::C)
+        .with_header("// Generated by cbindgen - do not edit by hand")
+        .generate()
+        .expect("Unable to generate bindings")
+        .write_to_file(out_path);
+}
+
+// In-crate registry of the exported surface, kept next to the functions it
+// describes so a new export is one line away from showing up in the header
+// instead of silently missing it. `cbindgen` itself walks the crate's AST
+// for the real signatures; this registry only has to stay in sync as a
+// human-readable index (struct layouts + opaque handles + prototypes) for
+// reviewers checking that `smallcodes.h` covers everything it should.
+pub struct ExportedHeaderSurface {
+    pub structs: &'static [&'static str],
+    pub opaque_handles: &'static [&'static str],
+    pub functions: &'static [&'static str],
+}
+
+pub const HEADER_SURFACE: ExportedHeaderSurface = ExportedHeaderSurface {
+    structs: &["Point", "Rectangle", "Buffer"],
+    opaque_handles: &["RustCalculator", "OpaqueRustType"],
+    functions: &[
+        "add",
+        "add_numbers",
+        "rust_greet",
+        "free_string",
+        "rust_last_error_message",
+        "rust_last_error_length",
+        "rust_last_error_clear",
+        "calculator_create",
+        "calculator_add",
+        "calculator_destroy",
+        "alloc_buffer",
+        "free_buffer",
+        "process_with_callback",
+        "do_work",
+    ],
+};
+
+
 //// Using libc
 // Manual FFI declarations (when bindgen not available)
 use libc::{c_int, c_void, c_char, size_t};
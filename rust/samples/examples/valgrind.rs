@@ -0,0 +1,90 @@
+//// Valgrind/Memcheck client request annotations
+/// Client requests are how a program under Valgrind talks back to the tool:
+/// a magic, architecture-specific instruction sequence that Valgrind's JIT
+/// recognizes and turns into a call into the tool (here, Memcheck) instead
+/// of actually executing. With the `valgrind` feature off this module is a
+/// set of no-ops, so normal builds are unaffected.
+
+// Request codes, lifted from Memcheck's `memcheck.h` (`VG_USERREQ__...`).
+#[cfg(feature = "valgrind")]
+mod request_ids {
+    pub const MALLOCLIKE_BLOCK: usize = 1301;
+    pub const FREELIKE_BLOCK: usize = 1302;
+    pub const MAKE_MEM_UNDEFINED: usize = 1305;
+}
+
+// Issues a Valgrind client request on x86_64 Linux/macOS: the `rolq`/`xchgq`
+// sequence is the magic prefix Valgrind's JIT pattern-matches; `%rdx` holds
+// the address of the `{request, arg1..arg4}` block, `%rax` receives the
+// tool's return value. On any other target this is a plain no-op, same as
+// running outside Valgrind.
+#[cfg(all(feature = "valgrind", target_arch = "x86_64"))]
+unsafe fn do_client_request(default: usize, args: [usize; 5]) -> usize {
+    let mut result = default;
+    std::arch::asm!(
+        "rol $$3,  %rdi",
+        "rol $$13, %rdi",
+        "rol $$61, %rdi",
+        "rol $$51, %rdi",
+        "xchg %rbx, %rbx",
+        inout("rdx") args.as_ptr() => _,
+        inout("rax") default => result,
+        options(att_syntax, nostack, preserves_flags),
+    );
+    result
+}
+
+#[cfg(all(feature = "valgrind", not(target_arch = "x86_64")))]
+unsafe fn do_client_request(default: usize, _args: [usize; 5]) -> usize {
+    default
+}
+
+/// Tells Memcheck that `[addr, addr+len)` is a heap block allocated at
+/// `addr`, as if by `malloc`, so unreferenced memory there is reported as a
+/// leak and double-frees at `addr` are caught.
+#[cfg(feature = "valgrind")]
+pub fn malloclike_block(addr: *const u8, len: usize) {
+    unsafe {
+        do_client_request(
+            0,
+            [
+                request_ids::MALLOCLIKE_BLOCK,
+                addr as usize,
+                len,
+                0, // rzB: no red zone
+                0, // is_zeroed: no
+            ],
+        );
+    }
+}
+
+/// Tells Memcheck that the block previously registered at `addr` has been
+/// freed, so further reads/writes to it are flagged as use-after-free.
+#[cfg(feature = "valgrind")]
+pub fn freelike_block(addr: *const u8) {
+    unsafe {
+        do_client_request(0, [request_ids::FREELIKE_BLOCK, addr as usize, 0, 0, 0]);
+    }
+}
+
+/// Marks `[addr, addr+len)` as undefined, matching what real `malloc`
+/// returns: reading it before writing should trip an uninitialized-value
+/// warning.
+#[cfg(feature = "valgrind")]
+pub fn make_mem_undefined(addr: *const u8, len: usize) {
+    unsafe {
+        do_client_request(
+            0,
+            [request_ids::MAKE_MEM_UNDEFINED, addr as usize, len, 0, 0],
+        );
+    }
+}
+
+#[cfg(not(feature = "valgrind"))]
+pub fn malloclike_block(_addr: *const u8, _len: usize) {}
+
+#[cfg(not(feature = "valgrind"))]
+pub fn freelike_block(_addr: *const u8) {}
+
+#[cfg(not(feature = "valgrind"))]
+pub fn make_mem_undefined(_addr: *const u8, _len: usize) {}
@@ -248,34 +248,183 @@ println!("{:?}", stepped);  // [1, 4, 7, 10]
 
 
 /// Stateful iterators, owns its state, next is cheap, return none after exhaustion
+// `u64` + `checked_add` stops after a few dozen terms, since Fibonacci grows
+// exponentially. Back it with an arbitrary-precision `BigUint` instead so
+// the iterator is only bounded by `.take(n)`, never by overflow.
+
+/// Little-endian limbs (least significant first), no trailing zero limb —
+/// an empty vec or `[0]` both represent zero.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct BigUint {
+    limbs: Vec<u64>,
+}
+
+impl BigUint {
+    fn zero() -> Self {
+        BigUint { limbs: vec![] }
+    }
+
+    fn from_u64(n: u64) -> Self {
+        if n == 0 {
+            BigUint::zero()
+        } else {
+            BigUint { limbs: vec![n] }
+        }
+    }
+
+    // Schoolbook addition: walk both limb vectors in lockstep, summing in a
+    // `u128` so the carry out of `u64 + u64 + carry` always fits.
+    fn add(&self, other: &BigUint) -> BigUint {
+        let mut limbs = Vec::with_capacity(self.limbs.len().max(other.limbs.len()) + 1);
+        let mut carry = 0u128;
+        for i in 0..self.limbs.len().max(other.limbs.len()) {
+            let a = *self.limbs.get(i).unwrap_or(&0) as u128;
+            let b = *other.limbs.get(i).unwrap_or(&0) as u128;
+            let sum = a + b + carry;
+            limbs.push(sum as u64);
+            carry = sum >> 64;
+        }
+        if carry > 0 {
+            limbs.push(carry as u64);
+        }
+        BigUint { limbs }
+    }
+}
+
+impl std::fmt::Display for BigUint {
+    // No native "big" arithmetic to print with, so repeatedly divide the
+    // limb vector by 10, collecting remainders as decimal digits.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.limbs.is_empty() {
+            return write!(f, "0");
+        }
+        let mut limbs = self.limbs.clone();
+        let mut digits = Vec::new();
+        while !limbs.is_empty() {
+            let mut remainder = 0u128;
+            for limb in limbs.iter_mut().rev() {
+                let acc = (remainder << 64) + *limb as u128;
+                *limb = (acc / 10) as u64;
+                remainder = acc % 10;
+            }
+            while limbs.last() == Some(&0) {
+                limbs.pop();
+            }
+            digits.push((b'0' + remainder as u8) as char);
+        }
+        write!(f, "{}", digits.iter().rev().collect::<String>())
+    }
+}
+
 struct Fibonacci {
-    current: u64,
-    next: u64,
+    current: BigUint,
+    next: BigUint,
 }
 
 impl Fibonacci {
     fn new() -> Self {
-        Fibonacci { current: 0, next: 1 }
+        Fibonacci {
+            current: BigUint::zero(),
+            next: BigUint::from_u64(1),
+        }
     }
 }
 
 impl Iterator for Fibonacci {
-    type Item = u64;
-    
+    type Item = BigUint;
+
     fn next(&mut self) -> Option<Self::Item> {
-        let new_next = self.current.checked_add(self.next)?;
-        let result = self.current;
-        self.current = self.next;
-        self.next = new_next;
+        let result = self.current.clone();
+        let new_next = self.current.add(&self.next);
+        self.current = std::mem::replace(&mut self.next, new_next);
         Some(result)
     }
 }
 
 // Infinite iterator with take
-let first_ten: Vec<u64> = Fibonacci::new().take(10).collect();
-// [0, 1, 1, 2, 3, 5, 8, 13, 21, 34] 
+let first_ten: Vec<BigUint> = Fibonacci::new().take(10).collect();
+// [0, 1, 1, 2, 3, 5, 8, 13, 21, 34]
+
+// No longer bounded by u64 overflow
+println!("{}", Fibonacci::new().nth(1000).unwrap());
+// 43466557686937456435688527675040625802564660517371780402481729089536555417949051890403879840079255169295922593080322634775209689623239873322471161642996440906533187938298969649928516003704476137795166849228875
 
 
+/// Length-prefixed protocol decoding, the custom-iterator version of the
+/// RLP/length-prefixed walks used to read serialized node and storage data
+#[derive(Debug, PartialEq, Eq)]
+enum DecodeError {
+    Truncated,
+}
+
+// Wraps a byte slice and yields one length-prefixed frame per `next()`:
+// a 4-byte big-endian length header followed by exactly that many payload
+// bytes. Allocation-light — only the yielded payload itself is copied out.
+struct FrameDecoder<'a> {
+    buf: &'a [u8],
+    pos: usize,
+    truncated: bool,
+}
+
+impl<'a> FrameDecoder<'a> {
+    fn new(buf: &'a [u8]) -> Self {
+        FrameDecoder { buf, pos: 0, truncated: false }
+    }
+}
+
+impl<'a> Iterator for FrameDecoder<'a> {
+    type Item = Result<Vec<u8>, DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.truncated {
+            return None;
+        }
+        // Exhausted right at a frame boundary - clean end of stream.
+        if self.pos == self.buf.len() {
+            return None;
+        }
+
+        let header_end = self.pos + 4;
+        if header_end > self.buf.len() {
+            self.truncated = true;
+            return Some(Err(DecodeError::Truncated));
+        }
+        let len = u32::from_be_bytes(self.buf[self.pos..header_end].try_into().unwrap()) as usize;
+
+        let payload_end = header_end + len;
+        if payload_end > self.buf.len() {
+            self.truncated = true;
+            return Some(Err(DecodeError::Truncated));
+        }
+
+        let payload = self.buf[header_end..payload_end].to_vec();
+        self.pos = payload_end;
+        Some(Ok(payload))
+    }
+}
+
+// Prefixes `payload` with its big-endian u32 length so it round-trips
+// through `FrameDecoder`.
+fn encode_frame(payload: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(4 + payload.len());
+    frame.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+    frame.extend_from_slice(payload);
+    frame
+}
+
+// Usage
+let mut wire = Vec::new();
+wire.extend(encode_frame(b"hello"));
+wire.extend(encode_frame(b"world"));
+let frames: Vec<_> = FrameDecoder::new(&wire).collect();
+// [Ok(b"hello".to_vec()), Ok(b"world".to_vec())]
+
+// A trailing partial frame yields one `Err(Truncated)`, then `None`
+let mut truncated_wire = encode_frame(b"hello");
+truncated_wire.extend_from_slice(&[0, 0, 0, 10, 1, 2]); // header claims 10 bytes, only 2 follow
+let frames: Vec<_> = FrameDecoder::new(&truncated_wire).collect();
+// [Ok(b"hello".to_vec()), Err(DecodeError::Truncated)]
+
 
 //// Closure Traits (Fn, FnMut, FnOnce), Fn ⊂ FnMut ⊂ FnOnce
 /// Fn captures by reference, read-only
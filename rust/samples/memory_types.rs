@@ -115,6 +115,155 @@ println!("Final: {:?}", shared_data.lock().unwrap());
 // 3. Combined with Mutex/RwLock for mutable shared data
 
 
+//// Arc from scratch: what the atomics buy you over Rc
+/// `Rc<T>`'s count is a plain `Cell<usize>`, so incrementing it from two
+/// threads at once is a data race. `MyArc<T>` swaps that for an
+/// `AtomicUsize` and picks orderings deliberately instead of reaching for
+/// `SeqCst` everywhere: a `clone()` only needs to not lose increments, so
+/// `Relaxed` is enough (fetch_add is already atomic; nothing downstream of
+/// it depends on memory another thread wrote). The decrement in `drop()`
+/// needs `Release` so that any writes this thread made through the
+/// pointer happen-before a concurrent final decrement sees count == 0 and
+/// frees. That last dropper still needs an explicit `Acquire` fence before
+/// running `T`'s destructor, or it could read a torn/stale `T` written by a
+/// thread whose `Release` it raced with.
+mod my_arc {
+    use std::ops::Deref;
+    use std::ptr::NonNull;
+    use std::sync::atomic::{self, AtomicUsize, Ordering};
+
+    struct ArcInner<T> {
+        strong: AtomicUsize,
+        value: T,
+    }
+
+    pub struct MyArc<T> {
+        ptr: NonNull<ArcInner<T>>,
+    }
+
+    // Safe under the same conditions as `std::sync::Arc`: sharing a
+    // `MyArc<T>` across threads only gives out `&T`, so `T` must be `Sync`;
+    // dropping the last handle on any thread runs `T`'s destructor, so `T`
+    // must also be `Send`.
+    unsafe impl<T: Send + Sync> Send for MyArc<T> {}
+    unsafe impl<T: Send + Sync> Sync for MyArc<T> {}
+
+    impl<T> MyArc<T> {
+        pub fn new(value: T) -> Self {
+            let inner = Box::new(ArcInner {
+                strong: AtomicUsize::new(1),
+                value,
+            });
+            Self {
+                // Safety: `Box::into_raw` never returns null.
+                ptr: unsafe { NonNull::new_unchecked(Box::into_raw(inner)) },
+            }
+        }
+
+        pub fn strong_count(this: &Self) -> usize {
+            this.inner().strong.load(Ordering::SeqCst)
+        }
+
+        fn inner(&self) -> &ArcInner<T> {
+            // Safety: as long as `self` exists, `strong` is at least 1, so
+            // the allocation hasn't been freed.
+            unsafe { self.ptr.as_ref() }
+        }
+    }
+
+    impl<T> Clone for MyArc<T> {
+        fn clone(&self) -> Self {
+            // Relaxed: merely bumping the count doesn't publish any new
+            // data to other threads, it just needs to not race-lose a
+            // concurrent increment -- `fetch_add` is atomic either way.
+            self.inner().strong.fetch_add(1, Ordering::Relaxed);
+            Self { ptr: self.ptr }
+        }
+    }
+
+    impl<T> Deref for MyArc<T> {
+        type Target = T;
+        fn deref(&self) -> &T {
+            &self.inner().value
+        }
+    }
+
+    impl<T> Drop for MyArc<T> {
+        fn drop(&mut self) {
+            // Release: every write this handle made through the pointer
+            // must happen-before whichever thread observes the count hit
+            // zero and frees.
+            if self.inner().strong.fetch_sub(1, Ordering::Release) != 1 {
+                return;
+            }
+
+            // Acquire fence: pairs with every `Release` decrement above,
+            // so this thread sees all of them before running `T`'s
+            // destructor -- otherwise it could read a value some other
+            // thread's drop-time write raced with.
+            atomic::fence(Ordering::Acquire);
+
+            // Safety: the count just hit zero and no other `MyArc` can
+            // exist (every clone held its own increment), so this is the
+            // sole owner of the allocation.
+            unsafe { drop(Box::from_raw(self.ptr.as_ptr())) };
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::MyArc;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+        use std::thread;
+
+        struct DropCounter(Arc<AtomicUsize>);
+
+        impl Drop for DropCounter {
+            fn drop(&mut self) {
+                self.0.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        #[test]
+        fn clone_across_threads_drops_the_payload_exactly_once() {
+            let drops = Arc::new(AtomicUsize::new(0));
+            let arc = MyArc::new(DropCounter(Arc::clone(&drops)));
+
+            let handles: Vec<_> = (0..32)
+                .map(|_| {
+                    let arc = arc.clone();
+                    thread::spawn(move || {
+                        // Touch the payload to ensure a live `&T` on every thread.
+                        let _ = &*arc;
+                    })
+                })
+                .collect();
+
+            for handle in handles {
+                handle.join().unwrap();
+            }
+
+            assert_eq!(MyArc::strong_count(&arc), 1);
+            assert_eq!(drops.load(Ordering::SeqCst), 0);
+
+            drop(arc);
+            assert_eq!(drops.load(Ordering::SeqCst), 1);
+        }
+
+        #[test]
+        fn strong_count_tracks_outstanding_clones() {
+            let arc = MyArc::new(5);
+            assert_eq!(MyArc::strong_count(&arc), 1);
+            let clone = arc.clone();
+            assert_eq!(MyArc::strong_count(&arc), 2);
+            drop(clone);
+            assert_eq!(MyArc::strong_count(&arc), 1);
+        }
+    }
+}
+
+
 // RefCell<T>, Interior Mutability for Single Thread, enforces borrow rules at runtime, panics on violation
 use std::cell::RefCell;
 
@@ -159,6 +308,199 @@ println!("{:?}", shared_mut.borrow());  // [1, 2, 3, 4, 5]
 // 4. Single-threaded scenarios only
 
 
+//// RefCell from scratch: what "enforces borrow rules at runtime" means
+/// `std::cell::RefCell` tracks its borrow state in a plain field and checks
+/// it on every `borrow`/`borrow_mut` call; there's no magic, just a guard
+/// type whose `Drop` impl undoes whatever the constructor recorded. This
+/// reimplementation makes that state explicit instead of hiding it inside
+/// the standard library.
+mod my_refcell {
+    use std::cell::UnsafeCell;
+    use std::fmt;
+    use std::ops::{Deref, DerefMut};
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum BorrowState {
+        Unshared,
+        Shared(usize),
+        Exclusive,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct BorrowError;
+
+    impl fmt::Display for BorrowError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "already mutably borrowed")
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct BorrowMutError;
+
+    impl fmt::Display for BorrowMutError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "already borrowed")
+        }
+    }
+
+    pub struct MyRefCell<T> {
+        state: UnsafeCell<BorrowState>,
+        value: UnsafeCell<T>,
+    }
+
+    impl<T> MyRefCell<T> {
+        pub fn new(value: T) -> Self {
+            Self {
+                state: UnsafeCell::new(BorrowState::Unshared),
+                value: UnsafeCell::new(value),
+            }
+        }
+
+        pub fn try_borrow(&self) -> Result<MyRef<'_, T>, BorrowError> {
+            // Safety: `MyRefCell` isn't `Sync`, so only one thread ever
+            // touches `state`, and no other reference to it is live here.
+            let state = unsafe { &mut *self.state.get() };
+            match *state {
+                BorrowState::Exclusive => Err(BorrowError),
+                BorrowState::Unshared => {
+                    *state = BorrowState::Shared(1);
+                    Ok(MyRef { cell: self })
+                }
+                BorrowState::Shared(n) => {
+                    *state = BorrowState::Shared(n + 1);
+                    Ok(MyRef { cell: self })
+                }
+            }
+        }
+
+        pub fn borrow(&self) -> MyRef<'_, T> {
+            self.try_borrow().expect("already mutably borrowed")
+        }
+
+        pub fn try_borrow_mut(&self) -> Result<MyRefMut<'_, T>, BorrowMutError> {
+            let state = unsafe { &mut *self.state.get() };
+            match *state {
+                BorrowState::Unshared => {
+                    *state = BorrowState::Exclusive;
+                    Ok(MyRefMut { cell: self })
+                }
+                BorrowState::Shared(_) | BorrowState::Exclusive => Err(BorrowMutError),
+            }
+        }
+
+        pub fn borrow_mut(&self) -> MyRefMut<'_, T> {
+            self.try_borrow_mut().expect("already borrowed")
+        }
+    }
+
+    pub struct MyRef<'a, T> {
+        cell: &'a MyRefCell<T>,
+    }
+
+    impl<'a, T> Deref for MyRef<'a, T> {
+        type Target = T;
+        fn deref(&self) -> &T {
+            // Safety: while this guard lives, `state` is `Shared(_)`, which
+            // `try_borrow_mut` refuses to upgrade, so no `&mut T` can alias.
+            unsafe { &*self.cell.value.get() }
+        }
+    }
+
+    impl<'a, T> Drop for MyRef<'a, T> {
+        fn drop(&mut self) {
+            let state = unsafe { &mut *self.cell.state.get() };
+            *state = match *state {
+                BorrowState::Shared(1) => BorrowState::Unshared,
+                BorrowState::Shared(n) => BorrowState::Shared(n - 1),
+                BorrowState::Unshared | BorrowState::Exclusive => {
+                    unreachable!("a live MyRef implies Shared(_)")
+                }
+            };
+        }
+    }
+
+    pub struct MyRefMut<'a, T> {
+        cell: &'a MyRefCell<T>,
+    }
+
+    impl<'a, T> Deref for MyRefMut<'a, T> {
+        type Target = T;
+        fn deref(&self) -> &T {
+            unsafe { &*self.cell.value.get() }
+        }
+    }
+
+    impl<'a, T> DerefMut for MyRefMut<'a, T> {
+        fn deref_mut(&mut self) -> &mut T {
+            // Safety: a live `MyRefMut` means `state` is `Exclusive`, which
+            // `try_borrow`/`try_borrow_mut` both refuse, so this is the only
+            // reference to `value` in existence.
+            unsafe { &mut *self.cell.value.get() }
+        }
+    }
+
+    impl<'a, T> Drop for MyRefMut<'a, T> {
+        fn drop(&mut self) {
+            let state = unsafe { &mut *self.cell.state.get() };
+            *state = BorrowState::Unshared;
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn multiple_shared_borrows_coexist() {
+            let cell = MyRefCell::new(5);
+            let a = cell.borrow();
+            let b = cell.borrow();
+            assert_eq!(*a, 5);
+            assert_eq!(*b, 5);
+        }
+
+        #[test]
+        fn mutable_borrow_excludes_shared_borrows() {
+            let cell = MyRefCell::new(5);
+            let _write = cell.borrow_mut();
+            assert_eq!(cell.try_borrow().err(), Some(BorrowError));
+        }
+
+        #[test]
+        fn mutable_borrow_excludes_another_mutable_borrow() {
+            let cell = MyRefCell::new(5);
+            let _write = cell.borrow_mut();
+            assert_eq!(cell.try_borrow_mut().err(), Some(BorrowMutError));
+        }
+
+        #[test]
+        #[should_panic(expected = "already borrowed")]
+        fn borrow_mut_panics_while_shared() {
+            let cell = MyRefCell::new(5);
+            let _read = cell.borrow();
+            cell.borrow_mut();
+        }
+
+        #[test]
+        fn state_is_restored_once_guards_drop() {
+            let cell = MyRefCell::new(5);
+            {
+                let _a = cell.borrow();
+                let _b = cell.borrow();
+            }
+            // Both shared borrows dropped, so an exclusive borrow succeeds.
+            {
+                let mut write = cell.borrow_mut();
+                *write += 1;
+            }
+            // And the exclusive borrow dropped, so shared borrows succeed again.
+            assert_eq!(*cell.borrow(), 6);
+        }
+    }
+}
+
+
 // Mutex<T>, Mutual Exclusion for Threads, thread-safe, blocks on contention
 use std::sync::Mutex;
 use std::thread;
@@ -415,4 +757,162 @@ x.set(x.get() + y.get());     // Read both, write x
 println!("x = {}, y = {}", x.get(), y.get());
 
 // No runtime borrowing checks needed
-// Because get() returns a copy, not a reference
\ No newline at end of file
+// Because get() returns a copy, not a reference
+
+
+//// Sharded Counter: Cell's no-borrow-overhead advantage at high frequency
+/// `Cell::get`/`set` skip the borrow-flag check `RefCell` does on every
+/// access, which matters once a counter is incremented millions of times
+/// on a hot path. The catch is `Cell<T>` is `!Sync`, so it can only ever
+/// back a counter confined to one thread. A "sharded" counter embraces
+/// that: give each thread (or a small fixed number of shards) its own
+/// `Cell<u64>` so increments never contend, and only pay for
+/// synchronization in `snapshot()`, which runs rarely by comparison. The
+/// `AtomicU64` variant below is the version of the same idea that's
+/// actually `Sync` -- the shards themselves can be shared and incremented
+/// concurrently, at the cost of an atomic RMW per increment instead of a
+/// plain store.
+mod sharded_counter {
+    use std::cell::Cell;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    /// Single-thread sharded counter. Each shard is a bare `Cell<u64>`, so
+    /// `CellCounter` is `!Sync` and the compiler keeps it on one thread;
+    /// callers pick which shard each increment lands in.
+    pub struct CellCounter {
+        shards: Vec<Cell<u64>>,
+    }
+
+    impl CellCounter {
+        pub fn new(shard_count: usize) -> Self {
+            Self {
+                shards: (0..shard_count).map(|_| Cell::new(0)).collect(),
+            }
+        }
+
+        pub fn increment(&self, shard: usize) {
+            let cell = &self.shards[shard];
+            cell.set(cell.get() + 1);
+        }
+
+        pub fn snapshot(&self) -> u64 {
+            self.shards.iter().map(Cell::get).sum()
+        }
+    }
+
+    /// Multi-thread counterpart: the same per-shard layout, but `AtomicU64`
+    /// shards so multiple threads can safely share and increment the same
+    /// `AtomicCounter` concurrently.
+    pub struct AtomicCounter {
+        shards: Vec<AtomicU64>,
+    }
+
+    impl AtomicCounter {
+        pub fn new(shard_count: usize) -> Self {
+            Self {
+                shards: (0..shard_count).map(|_| AtomicU64::new(0)).collect(),
+            }
+        }
+
+        pub fn increment(&self, shard: usize) {
+            self.shards[shard].fetch_add(1, Ordering::Relaxed);
+        }
+
+        pub fn snapshot(&self) -> u64 {
+            self.shards.iter().map(|shard| shard.load(Ordering::Relaxed)).sum()
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::{AtomicCounter, CellCounter};
+        use std::cell::Cell;
+        use std::sync::{Arc, Mutex};
+        use std::thread;
+        use std::time::Instant;
+
+        #[test]
+        fn cell_counter_sums_across_shards() {
+            let counter = CellCounter::new(4);
+            counter.increment(0);
+            counter.increment(0);
+            counter.increment(3);
+            assert_eq!(counter.snapshot(), 3);
+        }
+
+        #[test]
+        fn atomic_counter_sums_concurrent_increments() {
+            let counter = Arc::new(AtomicCounter::new(8));
+            let handles: Vec<_> = (0..8)
+                .map(|shard| {
+                    let counter = Arc::clone(&counter);
+                    thread::spawn(move || {
+                        for _ in 0..1_000 {
+                            counter.increment(shard);
+                        }
+                    })
+                })
+                .collect();
+
+            for handle in handles {
+                handle.join().unwrap();
+            }
+
+            assert_eq!(counter.snapshot(), 8 * 1_000);
+        }
+
+        // Benchmark-style (not a strict assertion, timings are noisy on
+        // CI): each thread owning an uncontended thread-local `Cell<u64>`
+        // should never be dramatically slower than every thread fighting
+        // over one `Mutex<u64>`, which is the whole motivation for
+        // sharding in the first place.
+        #[test]
+        fn cell_per_thread_is_not_slower_than_shared_mutex() {
+            let thread_count = 8;
+            let iterations = 200_000u64;
+
+            let cell_elapsed = {
+                let start = Instant::now();
+                let handles: Vec<_> = (0..thread_count)
+                    .map(|_| {
+                        thread::spawn(move || {
+                            let counter = Cell::new(0u64);
+                            for _ in 0..iterations {
+                                counter.set(counter.get() + 1);
+                            }
+                            counter.get()
+                        })
+                    })
+                    .collect();
+                for handle in handles {
+                    handle.join().unwrap();
+                }
+                start.elapsed()
+            };
+
+            let mutex_elapsed = {
+                let shared = Arc::new(Mutex::new(0u64));
+                let start = Instant::now();
+                let handles: Vec<_> = (0..thread_count)
+                    .map(|_| {
+                        let shared = Arc::clone(&shared);
+                        thread::spawn(move || {
+                            for _ in 0..iterations {
+                                *shared.lock().unwrap() += 1;
+                            }
+                        })
+                    })
+                    .collect();
+                for handle in handles {
+                    handle.join().unwrap();
+                }
+                start.elapsed()
+            };
+
+            assert!(
+                cell_elapsed <= mutex_elapsed * 4,
+                "cell-per-thread ({cell_elapsed:?}) unexpectedly slower than shared mutex ({mutex_elapsed:?})"
+            );
+        }
+    }
+}
\ No newline at end of file
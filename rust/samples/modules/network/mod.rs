@@ -4,32 +4,123 @@ pub mod client;
 
 // Also declare a server module defined inline
 pub mod server {
+    use std::fs;
+    use std::io;
+    use std::net::TcpListener;
+    #[cfg(unix)]
+    use std::os::unix::net::UnixListener;
+
+    // Abstracts over "however we accept connections", so `Server` doesn't
+    // need to know whether it's fronting a network socket or a local one.
+    pub trait Bindable: Sized {
+        fn bind(endpoint: &str, reuse: bool) -> io::Result<Self>;
+    }
+
+    // Either a TCP listener or (on Unix) a listener on a local socket file.
+    // The Unix variant carries its own path so `Drop` can unlink it.
+    pub enum Listener {
+        Tcp(TcpListener),
+        #[cfg(unix)]
+        Unix(UnixListener, String),
+    }
+
+    impl Bindable for Listener {
+        // `endpoint` is either "host:port" or "unix:/path/to/socket".
+        fn bind(endpoint: &str, reuse: bool) -> io::Result<Self> {
+            match endpoint.strip_prefix("unix:") {
+                Some(path) => Self::bind_unix(path, reuse),
+                None => Ok(Listener::Tcp(TcpListener::bind(endpoint)?)),
+            }
+        }
+    }
+
+    impl Listener {
+        #[cfg(unix)]
+        fn bind_unix(path: &str, reuse: bool) -> io::Result<Self> {
+            // A process that crashed without closing its listener leaves the
+            // socket file behind; `reuse` controls whether we clean it up
+            // ourselves or let `bind` fail with "address already in use".
+            if reuse && fs::metadata(path).is_ok() {
+                fs::remove_file(path)?;
+            }
+            Ok(Listener::Unix(UnixListener::bind(path)?, path.to_string()))
+        }
+
+        #[cfg(not(unix))]
+        fn bind_unix(_path: &str, _reuse: bool) -> io::Result<Self> {
+            Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "unix domain sockets are not supported on this platform",
+            ))
+        }
+    }
+
+    #[cfg(unix)]
+    impl Drop for Listener {
+        fn drop(&mut self) {
+            if let Listener::Unix(_, path) = self {
+                let _ = fs::remove_file(path);
+            }
+        }
+    }
+
     // Public struct
     pub struct Server {
-        address: String,  // Private field
-        pub port: u16,    // Public field
+        endpoint: String,         // Private field; "host:port" or "unix:/path"
+        reuse: bool,              // Remove a stale unix socket file before binding
+        listener: Option<Listener>,
+        negotiated_version: Option<super::ProtocolVersion>,
     }
-    
+
     impl Server {
         // Public constructor
-        pub fn new(address: &str, port: u16) -> Self {
+        pub fn new(endpoint: &str) -> Self {
             Server {
-                address: address.to_string(),
-                port,
+                endpoint: endpoint.to_string(),
+                reuse: false,
+                listener: None,
+                negotiated_version: None,
             }
         }
-        
+
+        // Builder-style toggle, since `reuse` only matters for unix sockets
+        // and most callers are happy with the default (fail on a stale file).
+        pub fn with_reuse(mut self, reuse: bool) -> Self {
+            self.reuse = reuse;
+            self
+        }
+
         // Public method
-        pub fn start(&self) {
-            println!("Server starting on {}:{}", self.address, self.port);
+        pub fn start(&mut self) -> io::Result<()> {
+            if !self.validate_address() {
+                return Err(io::Error::new(io::ErrorKind::InvalidInput, "empty endpoint"));
+            }
+
+            println!("Server starting on {}", self.endpoint);
+            self.listener = Some(Listener::bind(&self.endpoint, self.reuse)?);
+            Ok(())
         }
-        
+
+        // Server side of the version handshake: negotiates the client's
+        // announced version against `ProtocolVersion::CURRENT` and stores the
+        // result so later calls can gate optional features on it. It does no
+        // I/O; sending the reply is left to the caller.
+        pub fn negotiate(&mut self, client_version: super::ProtocolVersion) -> Result<super::ProtocolVersion, super::HandshakeError> {
+            let negotiated = super::negotiate(super::ProtocolVersion::CURRENT, client_version)?;
+            self.negotiated_version = Some(negotiated);
+            Ok(negotiated)
+        }
+
+        pub fn negotiated_version(&self) -> Option<super::ProtocolVersion> {
+            self.negotiated_version
+        }
+
         // Private method
         fn validate_address(&self) -> bool {
-            !self.address.is_empty()
+            !self.endpoint.is_empty()
         }
     }
-    
+
     // Public enum
     pub enum Protocol {
         Http,
@@ -41,13 +132,143 @@ pub mod server {
 // Re-export client's Client type for easier access
 pub use client::Client;
 
+// ============================================
+// PROTOCOL VERSION HANDSHAKE
+// ============================================
+
+// The wire version each side speaks. `connect` sends this first, `Server`
+// replies with its own, and both negotiate down to what they have in
+// common before any application message is exchanged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProtocolVersion {
+    pub major: u16,
+    pub minor: u16,
+}
+
+impl ProtocolVersion {
+    pub const CURRENT: ProtocolVersion = ProtocolVersion { major: 1, minor: 2 };
+
+    const BYTE_LEN: usize = 4; // major (u16 BE) + minor (u16 BE)
+}
+
+// Raised when the handshake can't proceed. A major mismatch is fatal
+// (the wire format itself may differ); anything else is a plain I/O error.
+#[derive(Debug)]
+pub enum HandshakeError {
+    IncompatibleMajor {
+        ours: ProtocolVersion,
+        theirs: ProtocolVersion,
+    },
+    Io(std::io::Error),
+}
+
+impl From<std::io::Error> for HandshakeError {
+    fn from(e: std::io::Error) -> Self {
+        HandshakeError::Io(e)
+    }
+}
+
+impl std::fmt::Display for HandshakeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            HandshakeError::IncompatibleMajor { ours, theirs } => write!(
+                f,
+                "incompatible protocol versions: we speak {}.{}, peer speaks {}.{}",
+                ours.major, ours.minor, theirs.major, theirs.minor
+            ),
+            HandshakeError::Io(e) => write!(f, "handshake I/O error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for HandshakeError {}
+
+// Compatibility rule: the major version must match exactly (it marks a
+// breaking wire-format change); a minor bump is assumed purely additive, so
+// two compatible peers simply agree to speak the lower of their minors.
+fn negotiate(ours: ProtocolVersion, theirs: ProtocolVersion) -> Result<ProtocolVersion, HandshakeError> {
+    if ours.major != theirs.major {
+        return Err(HandshakeError::IncompatibleMajor { ours, theirs });
+    }
+
+    Ok(ProtocolVersion {
+        major: ours.major,
+        minor: ours.minor.min(theirs.minor),
+    })
+}
+
+// Frame: [length: u8][major: u16 BE][minor: u16 BE]. The length prefix is
+// redundant today (the payload is always `BYTE_LEN`), but it's what lets a
+// future version grow the handshake payload without breaking older readers.
+fn write_version<W: std::io::Write>(w: &mut W, version: ProtocolVersion) -> std::io::Result<()> {
+    let mut frame = [0u8; 1 + ProtocolVersion::BYTE_LEN];
+    frame[0] = ProtocolVersion::BYTE_LEN as u8;
+    frame[1..3].copy_from_slice(&version.major.to_be_bytes());
+    frame[3..5].copy_from_slice(&version.minor.to_be_bytes());
+    w.write_all(&frame)
+}
+
+fn read_version<R: std::io::Read>(r: &mut R) -> std::io::Result<ProtocolVersion> {
+    let mut len_buf = [0u8; 1];
+    r.read_exact(&mut len_buf)?;
+    let len = len_buf[0] as usize;
+    if len != ProtocolVersion::BYTE_LEN {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("unexpected handshake frame length {len}"),
+        ));
+    }
+
+    let mut payload = [0u8; ProtocolVersion::BYTE_LEN];
+    r.read_exact(&mut payload)?;
+    Ok(ProtocolVersion {
+        major: u16::from_be_bytes([payload[0], payload[1]]),
+        minor: u16::from_be_bytes([payload[2], payload[3]]),
+    })
+}
+
+#[cfg(test)]
+mod handshake_tests {
+    use super::*;
+
+    #[test]
+    fn same_major_negotiates_the_lower_minor() {
+        let ours = ProtocolVersion { major: 1, minor: 3 };
+        let theirs = ProtocolVersion { major: 1, minor: 1 };
+        assert_eq!(negotiate(ours, theirs).unwrap(), ProtocolVersion { major: 1, minor: 1 });
+    }
+
+    #[test]
+    fn mismatched_major_is_rejected() {
+        let ours = ProtocolVersion { major: 2, minor: 0 };
+        let theirs = ProtocolVersion { major: 1, minor: 9 };
+        let err = negotiate(ours, theirs).unwrap_err();
+        assert!(matches!(err, HandshakeError::IncompatibleMajor { .. }));
+    }
+
+    #[test]
+    fn version_round_trips_through_the_wire_frame() {
+        let version = ProtocolVersion { major: 7, minor: 42 };
+        let mut buf = Vec::new();
+        write_version(&mut buf, version).unwrap();
+
+        let mut cursor = &buf[..];
+        assert_eq!(read_version(&mut cursor).unwrap(), version);
+    }
+}
+
 // Public function in network module
-pub fn connect(endpoint: &str) -> Result<(), String> {
+pub fn connect(endpoint: &str) -> Result<ProtocolVersion, String> {
     if endpoint.is_empty() {
-        Err("Empty endpoint".to_string())
-    } else {
-        Ok(())
+        return Err("Empty endpoint".to_string());
     }
+
+    // A real client would open a stream to `endpoint` here and run:
+    //   write_version(&mut stream, ProtocolVersion::CURRENT)?;
+    //   let theirs = read_version(&mut stream)?;
+    // This sample has no live transport, so it negotiates against the
+    // version it assumes its peer runs.
+    negotiate(ProtocolVersion::CURRENT, ProtocolVersion::CURRENT).map_err(|e| e.to_string())
 }
 
 // Private module only visible within network
@@ -58,7 +279,7 @@ mod internal {
 }
 
 // Use the internal module
-pub fn connect_and_log(endpoint: &str) -> Result<(), String> {
+pub fn connect_and_log(endpoint: &str) -> Result<ProtocolVersion, String> {
     let result = connect(endpoint);
     super::internal::log_connection();  // Call internal function
     result
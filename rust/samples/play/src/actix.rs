@@ -3,11 +3,40 @@
 // actix-web = "4"
 // serde = { version = "1.0", features = ["derive"] }
 // serde_json = "1.0"
-// reqwest = { version = "0.11", features = ["json"] }
+// reqwest = { version = "0.11", features = ["json", "rustls-tls"] }
 // tokio = { version = "1", features = ["full"] }
+// jsonwebtoken = "9"
+// anyhow = "1"
+// rsa = { version = "0.9", features = ["pem"] }
+// base64 = "0.21"
+// rand = "0.8"
+// async-trait = "0.1"
+// uuid = { version = "1", features = ["v4"] }
+// redis = { version = "0.23", features = ["tokio-comp", "connection-manager"] }
+// rustls = "0.21"
+// rustls-pemfile = "1"
+// webpki-roots = "0.25"
+// sha2 = "0.10"
+// x509-parser = "0.15"
+// futures-util = "0.3"
 
-use actix_web::{web, App, HttpResponse, HttpServer, Result};
+use actix_web::{
+    dev::Payload, http::{header::{HeaderName, HeaderValue, IntoHeaderPair}, StatusCode},
+    web, App, FromRequest, HttpRequest, HttpResponse, HttpServer, Responder, ResponseError, Result,
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use derive_more::{Display, Error};
+use jsonwebtoken::{decode, decode_header, encode, Algorithm, DecodingKey, EncodingKey, Header as JwtHeader, Validation};
+use rsa::pkcs1::{EncodeRsaPrivateKey, EncodeRsaPublicKey};
+use rsa::traits::PublicKeyParts;
+use rsa::{RsaPrivateKey, RsaPublicKey};
 use serde::{Deserialize, Serialize};
+use sha2::Digest;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 // ============================================
 // ESTRUTURAS DE DADOS
@@ -39,6 +68,222 @@ struct PostProcessado {
     tamanho_original: usize,
 }
 
+// Estrutura de resposta de get_usuario
+#[derive(Serialize)]
+struct UsuarioResponse {
+    id: i32,
+    nome: String,
+    ativo: bool,
+}
+
+// Estrutura de resposta de buscar_posts_externos
+#[derive(Serialize)]
+struct PostsResponse {
+    total_processado: usize,
+    posts: Vec<PostProcessado>,
+}
+
+// ============================================
+// RESPONDER CUSTOMIZÁVEL
+// ============================================
+
+// `.customize()` em qualquer `Responder`, para sobrescrever status e
+// cabeçalhos de forma fluente antes da serialização, sem montar a resposta
+// na mão com `serde_json::json!` toda vez.
+trait ResponderExt: Responder + Sized {
+    fn customize(self) -> CustomizeResponder<Self> {
+        CustomizeResponder {
+            inner: self,
+            status: None,
+            headers: Vec::new(),
+        }
+    }
+}
+
+impl<T: Responder> ResponderExt for T {}
+
+struct CustomizeResponder<T> {
+    inner: T,
+    status: Option<StatusCode>,
+    headers: Vec<(HeaderName, HeaderValue)>,
+}
+
+impl<T> CustomizeResponder<T> {
+    fn with_status(mut self, status: StatusCode) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    fn insert_header(mut self, header: impl IntoHeaderPair) -> Self {
+        let (name, value) = header.try_into_pair().expect("valid header");
+        self.headers.push((name, value));
+        self
+    }
+}
+
+impl<T: Responder> Responder for CustomizeResponder<T> {
+    type Body = T::Body;
+
+    fn respond_to(self, req: &HttpRequest) -> HttpResponse<Self::Body> {
+        let mut res = self.inner.respond_to(req);
+        if let Some(status) = self.status {
+            *res.status_mut() = status;
+        }
+        for (name, value) in self.headers {
+            res.headers_mut().insert(name, value);
+        }
+        res
+    }
+}
+
+impl Responder for PostProcessado {
+    type Body = actix_web::body::BoxBody;
+
+    fn respond_to(self, req: &HttpRequest) -> HttpResponse<Self::Body> {
+        HttpResponse::Ok().json(self).respond_to(req)
+    }
+}
+
+impl Responder for UsuarioResponse {
+    type Body = actix_web::body::BoxBody;
+
+    fn respond_to(self, req: &HttpRequest) -> HttpResponse<Self::Body> {
+        HttpResponse::Ok().json(self).respond_to(req)
+    }
+}
+
+impl Responder for PostsResponse {
+    type Body = actix_web::body::BoxBody;
+
+    fn respond_to(self, req: &HttpRequest) -> HttpResponse<Self::Body> {
+        HttpResponse::Ok().json(self).respond_to(req)
+    }
+}
+
+// ============================================
+// EXTRATOR DE CLIENTE AUTENTICADO
+// ============================================
+
+// Cliente autenticado, construído a partir do token Bearer do cabeçalho
+// Authorization. Extraído diretamente nos handlers, sem parsing manual.
+pub struct AuthenticatedClient {
+    pub id: u64,
+    pub name: String,
+}
+
+#[derive(Debug, Display, Error)]
+pub enum AuthError {
+    #[display(fmt = "Authorization header is missing")]
+    MissingToken,
+    #[display(fmt = "Authorization token is invalid")]
+    InvalidToken,
+}
+
+impl ResponseError for AuthError {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::UNAUTHORIZED
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(serde_json::json!({
+            "erro": self.to_string()
+        }))
+    }
+}
+
+// Tokens são do formato "Bearer <id>:<nome>" (ex.: "Bearer 7:joao") - em
+// produção isso validaria uma assinatura JWT ou uma sessão de verdade.
+fn parse_bearer_token(token: &str) -> Option<(u64, String)> {
+    let (id, name) = token.split_once(':')?;
+    let id: u64 = id.parse().ok()?;
+    if name.is_empty() {
+        return None;
+    }
+    Some((id, name.to_string()))
+}
+
+impl FromRequest for AuthenticatedClient {
+    type Error = AuthError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        let header = req
+            .headers()
+            .get(actix_web::http::header::AUTHORIZATION)
+            .and_then(|h| h.to_str().ok())
+            .map(|s| s.to_string());
+
+        Box::pin(async move {
+            let token = header
+                .as_deref()
+                .and_then(|h| h.strip_prefix("Bearer "))
+                .ok_or(AuthError::MissingToken)?;
+
+            let (id, name) = parse_bearer_token(token).ok_or(AuthError::InvalidToken)?;
+            Ok(AuthenticatedClient { id, name })
+        })
+    }
+}
+
+// ============================================
+// ERRO UNIFICADO DA APLICAÇÃO
+// ============================================
+
+// Erro único para os handlers que fazem criação de token, parsing de JSON
+// e chamadas `reqwest`, propagado com `?`. Cada variante carrega o
+// suficiente para montar um corpo JSON estruturado em vez de um 400/500 opaco.
+#[derive(Debug, Display, Error)]
+pub enum AppError {
+    #[display(fmt = "falha ao processar token: {}", _0)]
+    Jwt(jsonwebtoken::errors::Error),
+    #[display(fmt = "{}", message)]
+    Validation { field: String, message: String },
+    #[display(fmt = "falha ao consultar serviço externo: {}", _0)]
+    Upstream(String),
+    #[display(fmt = "{}", _0)]
+    Missing(String),
+}
+
+impl From<jsonwebtoken::errors::Error> for AppError {
+    fn from(e: jsonwebtoken::errors::Error) -> Self {
+        AppError::Jwt(e)
+    }
+}
+
+impl From<reqwest::Error> for AppError {
+    fn from(e: reqwest::Error) -> Self {
+        AppError::Upstream(e.to_string())
+    }
+}
+
+impl From<anyhow::Error> for AppError {
+    fn from(e: anyhow::Error) -> Self {
+        AppError::Upstream(e.to_string())
+    }
+}
+
+impl ResponseError for AppError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            AppError::Jwt(_) => StatusCode::UNAUTHORIZED,
+            AppError::Validation { .. } => StatusCode::BAD_REQUEST,
+            AppError::Upstream(_) => StatusCode::BAD_GATEWAY,
+            AppError::Missing(_) => StatusCode::NOT_FOUND,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        let body = match self {
+            AppError::Validation { field, message } => serde_json::json!({
+                "erro": message,
+                "campo": field,
+            }),
+            _ => serde_json::json!({ "erro": self.to_string() }),
+        };
+        HttpResponse::build(self.status_code()).json(body)
+    }
+}
+
 // ============================================
 // HANDLERS (Funções que tratam as requisições)
 // ============================================
@@ -51,26 +296,435 @@ async fn index() -> Result<HttpResponse> {
     })))
 }
 
+// ============================================
+// CHAVES ASSIMÉTRICAS E JWKS
+// ============================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+enum TokenType {
+    #[serde(rename = "access")]
+    Access,
+    #[serde(rename = "refresh")]
+    Refresh,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Claims {
+    sub: String,
+    exp: usize,
+    // Identifica este token individualmente, para que possa ser revogado ou
+    // marcado como já-usado (rotação de refresh token) sem invalidar outros.
+    jti: String,
+    // Snapshot da geração do usuário no momento da emissão: um "revoke all"
+    // incrementa a geração, então qualquer token com geração antiga falha
+    // mesmo sem ter seu `jti` individual revogado.
+    gen: u64,
+    typ: TokenType,
+}
+
+#[derive(Deserialize)]
+struct Credenciais {
+    nome: String,
+    senha: String,
+}
+
+#[derive(Serialize)]
+struct TokenResponse {
+    access_token: String,
+    refresh_token: String,
+}
+
+// ============================================
+// TOKEN STORE: revogação e detecção de replay
+// ============================================
+
+// Abstrai onde o estado de revogação mora, para que o mesmo fluxo de login
+// funcione com um backend em memória (testes, single-node) ou Redis
+// (múltiplas instâncias compartilhando o mesmo estado de revogação).
+#[async_trait::async_trait]
+trait TokenStore: Send + Sync {
+    async fn is_revoked(&self, jti: &str) -> bool;
+    async fn revoke(&self, jti: &str);
+    async fn generation_of(&self, user: &str) -> u64;
+    // Incrementa e retorna a nova geração, invalidando todo token emitido antes dela.
+    async fn bump_generation(&self, user: &str) -> u64;
+}
+
+#[derive(Default)]
+struct InMemoryTokenStore {
+    revoked: Mutex<std::collections::HashSet<String>>,
+    generations: Mutex<HashMap<String, u64>>,
+}
+
+#[async_trait::async_trait]
+impl TokenStore for InMemoryTokenStore {
+    async fn is_revoked(&self, jti: &str) -> bool {
+        self.revoked.lock().unwrap().contains(jti)
+    }
+
+    async fn revoke(&self, jti: &str) {
+        self.revoked.lock().unwrap().insert(jti.to_string());
+    }
+
+    async fn generation_of(&self, user: &str) -> u64 {
+        *self.generations.lock().unwrap().get(user).unwrap_or(&0)
+    }
+
+    async fn bump_generation(&self, user: &str) -> u64 {
+        let mut generations = self.generations.lock().unwrap();
+        let next = generations.get(user).copied().unwrap_or(0) + 1;
+        generations.insert(user.to_string(), next);
+        next
+    }
+}
+
+struct RedisTokenStore {
+    conn: redis::aio::ConnectionManager,
+    // The revocation entry outlives the refresh token by at least the token's
+    // own TTL, so a replayed/rotated jti is still rejected right up to expiry.
+    revocation_ttl: std::time::Duration,
+}
+
+impl RedisTokenStore {
+    async fn connect(url: &str, revocation_ttl: std::time::Duration) -> redis::RedisResult<Self> {
+        let client = redis::Client::open(url)?;
+        let conn = client.get_tokio_connection_manager().await?;
+        Ok(Self { conn, revocation_ttl })
+    }
+}
+
+#[async_trait::async_trait]
+impl TokenStore for RedisTokenStore {
+    async fn is_revoked(&self, jti: &str) -> bool {
+        let mut conn = self.conn.clone();
+        redis::cmd("EXISTS")
+            .arg(format!("revoked:{jti}"))
+            .query_async::<_, bool>(&mut conn)
+            .await
+            .unwrap_or(false)
+    }
+
+    async fn revoke(&self, jti: &str) {
+        let mut conn = self.conn.clone();
+        let _: redis::RedisResult<()> = redis::cmd("SET")
+            .arg(format!("revoked:{jti}"))
+            .arg(1)
+            .arg("EX")
+            .arg(self.revocation_ttl.as_secs())
+            .query_async(&mut conn)
+            .await;
+    }
+
+    async fn generation_of(&self, user: &str) -> u64 {
+        let mut conn = self.conn.clone();
+        redis::cmd("GET")
+            .arg(format!("gen:{user}"))
+            .query_async::<_, Option<u64>>(&mut conn)
+            .await
+            .ok()
+            .flatten()
+            .unwrap_or(0)
+    }
+
+    async fn bump_generation(&self, user: &str) -> u64 {
+        let mut conn = self.conn.clone();
+        redis::cmd("INCR")
+            .arg(format!("gen:{user}"))
+            .query_async(&mut conn)
+            .await
+            .unwrap_or(1)
+    }
+}
+
+type SharedTokenStore = web::Data<dyn TokenStore>;
+
+fn expiracao_em(segundos: u64) -> usize {
+    (SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("relógio do sistema antes da época Unix")
+        .as_secs()
+        + segundos) as usize
+}
+
+// Representação JWK (RFC 7517) de uma chave pública RSA, só com os campos
+// que um verificador precisa para reconstruir a chave.
+#[derive(Serialize)]
+struct Jwk {
+    kty: &'static str,
+    #[serde(rename = "use")]
+    uso: &'static str,
+    alg: &'static str,
+    kid: String,
+    n: String,
+    e: String,
+}
+
+#[derive(Serialize)]
+struct JwkSet {
+    keys: Vec<Jwk>,
+}
+
+// Um par de chaves RSA identificado por `kid`, usado para assinar (chave
+// privada) ou verificar (chave pública) tokens RS256.
+struct SigningKey {
+    kid: String,
+    encoding_key: EncodingKey,
+    decoding_key: DecodingKey,
+    public: RsaPublicKey,
+}
+
+impl SigningKey {
+    fn generate(kid: String) -> Self {
+        let mut rng = rand::thread_rng();
+        let private = RsaPrivateKey::new(&mut rng, 2048).expect("geração de chave RSA");
+        let public = private.to_public_key();
+
+        let encoding_key = EncodingKey::from_rsa_pem(
+            private
+                .to_pkcs1_pem(Default::default())
+                .expect("PEM da chave privada")
+                .as_bytes(),
+        )
+        .expect("PEM de chave privada RSA válido");
+        let decoding_key = DecodingKey::from_rsa_pem(
+            public
+                .to_pkcs1_pem(Default::default())
+                .expect("PEM da chave pública")
+                .as_bytes(),
+        )
+        .expect("PEM de chave pública RSA válido");
+
+        SigningKey {
+            kid,
+            encoding_key,
+            decoding_key,
+            public,
+        }
+    }
+
+    fn to_jwk(&self) -> Jwk {
+        Jwk {
+            kty: "RSA",
+            uso: "sig",
+            alg: "RS256",
+            kid: self.kid.clone(),
+            n: URL_SAFE_NO_PAD.encode(self.public.n().to_bytes_be()),
+            e: URL_SAFE_NO_PAD.encode(self.public.e().to_bytes_be()),
+        }
+    }
+}
+
+// Guarda todo par de chaves ainda válido para verificação, mais qual delas
+// assina tokens novos. `rotate` gera uma chave nova e a torna a atual sem
+// descartar as anteriores, então tokens já emitidos continuam validando
+// até expirar - o verificador escolhe a chave certa pelo `kid` do header.
+pub struct KeyRing {
+    keys: HashMap<String, SigningKey>,
+    current_kid: String,
+}
+
+impl KeyRing {
+    fn generate() -> Self {
+        let mut ring = KeyRing {
+            keys: HashMap::new(),
+            current_kid: String::new(),
+        };
+        ring.rotate();
+        ring
+    }
+
+    fn rotate(&mut self) -> String {
+        let kid = format!("key-{}", self.keys.len() + 1);
+        let key = SigningKey::generate(kid.clone());
+        self.keys.insert(kid.clone(), key);
+        self.current_kid = kid.clone();
+        kid
+    }
+
+    fn sign<T: Serialize>(&self, claims: &T) -> jsonwebtoken::errors::Result<String> {
+        let key = &self.keys[&self.current_kid];
+        let mut header = JwtHeader::new(Algorithm::RS256);
+        header.kid = Some(key.kid.clone());
+        encode(&header, claims, &key.encoding_key)
+    }
+
+    fn verify<T: serde::de::DeserializeOwned>(&self, token: &str, validate_exp: bool) -> jsonwebtoken::errors::Result<T> {
+        let kid = decode_header(token)?
+            .kid
+            .ok_or_else(|| jsonwebtoken::errors::Error::from(jsonwebtoken::errors::ErrorKind::InvalidToken))?;
+        let key = self.keys.get(&kid).ok_or_else(|| {
+            jsonwebtoken::errors::Error::from(jsonwebtoken::errors::ErrorKind::InvalidKeyFormat)
+        })?;
+
+        let mut validation = Validation::new(Algorithm::RS256);
+        validation.validate_exp = validate_exp;
+        Ok(decode::<T>(token, &key.decoding_key, &validation)?.claims)
+    }
+
+    fn jwks(&self) -> JwkSet {
+        JwkSet {
+            keys: self.keys.values().map(SigningKey::to_jwk).collect(),
+        }
+    }
+}
+
+type SharedKeyRing = web::Data<Mutex<KeyRing>>;
+
+fn create_token(key_ring: &KeyRing, sub: &str, gen: u64, typ: TokenType, ttl_secs: u64) -> jsonwebtoken::errors::Result<(String, String)> {
+    let jti = uuid::Uuid::new_v4().to_string();
+    let token = key_ring.sign(&Claims {
+        sub: sub.to_string(),
+        exp: expiracao_em(ttl_secs),
+        jti: jti.clone(),
+        gen,
+        typ,
+    })?;
+    Ok((token, jti))
+}
+
+async fn issue_pair(key_ring: &KeyRing, store: &dyn TokenStore, sub: &str) -> std::result::Result<TokenResponse, AppError> {
+    let gen = store.generation_of(sub).await;
+    let (access_token, _) = create_token(key_ring, sub, gen, TokenType::Access, 900)?;
+    let (refresh_token, _) = create_token(key_ring, sub, gen, TokenType::Refresh, 7 * 24 * 3600)?;
+    Ok(TokenResponse { access_token, refresh_token })
+}
+
+// Verifica assinatura, expiração, geração e revogação/replay em um só lugar,
+// tanto para tokens de acesso quanto de refresh.
+async fn validate_token(
+    key_ring: &KeyRing,
+    store: &dyn TokenStore,
+    token: &str,
+    expected: TokenType,
+) -> std::result::Result<Claims, AppError> {
+    let claims: Claims = key_ring.verify(token, true)?;
+
+    if claims.typ != expected {
+        return Err(AppError::Validation {
+            field: "token".to_string(),
+            message: "tipo de token incorreto".to_string(),
+        });
+    }
+    if store.is_revoked(&claims.jti).await {
+        return Err(AppError::Validation {
+            field: "token".to_string(),
+            message: "token revogado ou já utilizado".to_string(),
+        });
+    }
+    if claims.gen != store.generation_of(&claims.sub).await {
+        return Err(AppError::Validation {
+            field: "token".to_string(),
+            message: "token invalidado por revogação global".to_string(),
+        });
+    }
+
+    Ok(claims)
+}
+
+fn bearer_token(req: &HttpRequest) -> std::result::Result<&str, AppError> {
+    req.headers()
+        .get(actix_web::http::header::AUTHORIZATION)
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.strip_prefix("Bearer "))
+        .ok_or_else(|| AppError::Missing("cabeçalho Authorization ausente".to_string()))
+}
+
+// POST /login - Troca credenciais por um par de tokens (acesso + refresh)
+// assinados com a chave RS256 atual, cada um com seu próprio `jti`.
+async fn login(
+    credenciais: web::Json<Credenciais>,
+    key_ring: SharedKeyRing,
+    store: SharedTokenStore,
+) -> std::result::Result<HttpResponse, AppError> {
+    if credenciais.senha != "senha-correta" {
+        return Err(AppError::Validation {
+            field: "senha".to_string(),
+            message: "credenciais inválidas".to_string(),
+        });
+    }
+
+    let key_ring = key_ring.lock().unwrap();
+    let tokens = issue_pair(&key_ring, store.as_ref(), &credenciais.nome).await?;
+    Ok(HttpResponse::Ok().json(tokens))
+}
+
+// POST /refresh - Rotaciona o refresh token: o apresentado é revogado
+// imediatamente, então reapresentá-lo (replay) falha mesmo que ainda não
+// tenha expirado.
+async fn refresh_token(
+    req: HttpRequest,
+    key_ring: SharedKeyRing,
+    store: SharedTokenStore,
+) -> std::result::Result<HttpResponse, AppError> {
+    let token = bearer_token(&req)?;
+
+    let key_ring = key_ring.lock().unwrap();
+    let claims = validate_token(&key_ring, store.as_ref(), token, TokenType::Refresh).await?;
+
+    store.revoke(&claims.jti).await;
+    let tokens = issue_pair(&key_ring, store.as_ref(), &claims.sub).await?;
+    Ok(HttpResponse::Ok().json(tokens))
+}
+
+// POST /logout - Revoga o token apresentado (acesso ou refresh) individualmente.
+async fn logout(
+    req: HttpRequest,
+    key_ring: SharedKeyRing,
+    store: SharedTokenStore,
+) -> std::result::Result<HttpResponse, AppError> {
+    let token = bearer_token(&req)?;
+    // Não exigimos `exp` válido aqui: um token já expirado ainda pode (e
+    // deve) ser aceito para logout, já que o objetivo é só garantir que seu
+    // `jti` nunca mais valide, mesmo que a janela de expiração ainda não
+    // tenha virado.
+    let claims: Claims = key_ring.lock().unwrap().verify(token, false)?;
+
+    store.revoke(&claims.jti).await;
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "mensagem": "sessão encerrada" })))
+}
+
+// POST /admin/revoke/{usuario} - Invalida de uma vez todo token já emitido
+// para o usuário, bumpando sua geração.
+async fn revogar_todos(path: web::Path<String>, store: SharedTokenStore) -> HttpResponse {
+    let nova_geracao = store.bump_generation(&path.into_inner()).await;
+    HttpResponse::Ok().json(serde_json::json!({ "geracao": nova_geracao }))
+}
+
+// GET /.well-known/jwks.json - Expõe as chaves públicas vigentes em formato
+// JWK, para que outros serviços verifiquem tokens sem o segredo privado.
+async fn jwks(key_ring: SharedKeyRing) -> HttpResponse {
+    HttpResponse::Ok().json(key_ring.lock().unwrap().jwks())
+}
+
 // GET com parâmetro na URL - Ex: /usuarios/123
-async fn get_usuario(path: web::Path<i32>) -> Result<HttpResponse> {
+async fn get_usuario(path: web::Path<i32>) -> impl Responder {
     let user_id = path.into_inner();
-    
+
     // Simula busca de usuário (em produção, buscaria no banco de dados)
-    Ok(HttpResponse::Ok().json(serde_json::json!({
-        "id": user_id,
-        "nome": format!("Usuário {}", user_id),
-        "ativo": true
-    })))
+    UsuarioResponse {
+        id: user_id,
+        nome: format!("Usuário {}", user_id),
+        ativo: true,
+    }
+    .customize()
+    .insert_header(("X-Source", "cache-simulada"))
 }
 
 // POST - Recebe dados JSON no body
-async fn criar_usuario(usuario: web::Json<Usuario>) -> Result<HttpResponse> {
+async fn criar_usuario(
+    client: AuthenticatedClient,
+    usuario: web::Json<Usuario>,
+) -> Result<HttpResponse> {
     // Acessa os dados recebidos
-    println!("Recebido: {} - {}", usuario.nome, usuario.email);
-    
+    println!(
+        "Recebido de {} (id {}): {} - {}",
+        client.name, client.id, usuario.nome, usuario.email
+    );
+
     // Retorna confirmação com os dados processados
     Ok(HttpResponse::Created().json(serde_json::json!({
         "mensagem": "Usuário criado com sucesso!",
+        "criado_por": client.name,
         "usuario": {
             "nome": usuario.nome,
             "email": usuario.email,
@@ -81,14 +735,16 @@ async fn criar_usuario(usuario: web::Json<Usuario>) -> Result<HttpResponse> {
 
 // PUT - Atualiza um recurso existente
 async fn atualizar_usuario(
+    client: AuthenticatedClient,
     path: web::Path<i32>,
     usuario: web::Json<Usuario>
 ) -> Result<HttpResponse> {
     let user_id = path.into_inner();
-    
+
     Ok(HttpResponse::Ok().json(serde_json::json!({
         "mensagem": "Usuário atualizado!",
         "id": user_id,
+        "atualizado_por": client.name,
         "novos_dados": {
             "nome": usuario.nome,
             "email": usuario.email
@@ -120,71 +776,282 @@ async fn atualizar_parcial(
     })))
 }
 
-// GET que chama API externa - Busca posts e processa os dados
-async fn buscar_posts_externos() -> Result<HttpResponse> {
-    // Cria um cliente HTTP
-    let cliente = reqwest::Client::new();
-    
-    // Faz requisição GET para a API JSONPlaceholder (API pública de testes)
-    let resposta = cliente
-        .get("https://jsonplaceholder.typicode.com/posts")
-        .send()
-        .await;
-    
-    // Trata possíveis erros na requisição
-    match resposta {
-        Ok(resp) => {
-            // Converte a resposta para JSON
-            match resp.json::<Vec<Post>>().await {
-                Ok(posts) => {
-                    // Processa os dados: pega apenas os 5 primeiros posts
-                    // e cria um resumo de cada um
-                    let posts_processados: Vec<PostProcessado> = posts
-                        .into_iter()
-                        .take(5) // Limita a 5 posts
-                        .map(|post| {
-                            // Cria um resumo com os primeiros 50 caracteres
-                            let resumo = if post.body.len() > 50 {
-                                format!("{}...", &post.body[..50])
-                            } else {
-                                post.body.clone()
-                            };
-                            
-                            // Retorna estrutura processada
-                            PostProcessado {
-                                id: post.id,
-                                titulo: post.title,
-                                resumo,
-                                tamanho_original: post.body.len(),
-                            }
-                        })
-                        .collect();
-                    
-                    // Retorna os dados processados
-                    Ok(HttpResponse::Ok().json(serde_json::json!({
-                        "total_processado": posts_processados.len(),
-                        "posts": posts_processados
-                    })))
-                }
-                Err(e) => {
-                    // Erro ao fazer parse do JSON
-                    Ok(HttpResponse::InternalServerError().json(serde_json::json!({
-                        "erro": "Falha ao processar resposta da API",
-                        "detalhes": e.to_string()
-                    })))
+// ============================================
+// TLS DO CLIENTE UPSTREAM: rustls com pinning e mTLS opcionais
+// ============================================
+
+// Verifica a cadeia normalmente contra `roots` e, se `pins` não estiver
+// vazio, também exige que a SPKI (SHA-256) do certificado-folha esteja na
+// allow-list - derruba o handshake mesmo que a CA seja confiável, pra
+// proteger contra uma CA comprometida ou mal-emitida.
+struct PinningVerifier {
+    roots: rustls::RootCertStore,
+    pins: Vec<[u8; 32]>,
+}
+
+impl rustls::client::ServerCertVerifier for PinningVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &rustls::Certificate,
+        intermediates: &[rustls::Certificate],
+        server_name: &rustls::ServerName,
+        scts: &mut dyn Iterator<Item = &[u8]>,
+        ocsp_response: &[u8],
+        now: std::time::SystemTime,
+    ) -> std::result::Result<rustls::client::ServerCertVerified, rustls::Error> {
+        let webpki = rustls::client::WebPkiVerifier::new(self.roots.clone(), None);
+        webpki.verify_server_cert(end_entity, intermediates, server_name, scts, ocsp_response, now)?;
+
+        if self.pins.is_empty() {
+            return Ok(rustls::client::ServerCertVerified::assertion());
+        }
+
+        let spki = extract_spki(end_entity)
+            .map_err(|e| rustls::Error::General(format!("não foi possível extrair a SPKI: {e}")))?;
+        let hash: [u8; 32] = sha2::Sha256::digest(&spki).into();
+        if self.pins.contains(&hash) {
+            Ok(rustls::client::ServerCertVerified::assertion())
+        } else {
+            Err(rustls::Error::General(
+                "SPKI do certificado não está na allow-list de pinning".to_string(),
+            ))
+        }
+    }
+}
+
+fn extract_spki(cert: &rustls::Certificate) -> anyhow::Result<Vec<u8>> {
+    let (_, parsed) = x509_parser::parse_x509_certificate(&cert.0)
+        .map_err(|e| anyhow::anyhow!("certificado inválido: {e}"))?;
+    Ok(parsed.tbs_certificate.subject_pki.raw.to_vec())
+}
+
+#[derive(Default, Clone)]
+pub struct TlsConfig {
+    // PEM da CA customizada; se ausente, usa as raízes públicas do webpki.
+    pub root_pem: Option<Vec<u8>>,
+    // Par cert+chave (PEM) para autenticação mútua (mTLS) com o upstream.
+    pub client_identity_pem: Option<(Vec<u8>, Vec<u8>)>,
+    // Resolve o host para um endereço diferente mantendo o Host/SNI
+    // originais no handshake - útil quando o upstream vive atrás de um IP
+    // que não bate com o DNS público do certificado.
+    pub connect_override: Option<(String, std::net::SocketAddr)>,
+    // Hashes SHA-256 da SPKI de certificados aceitos; vazio desliga o pinning.
+    pub pinned_spki_sha256: Vec<[u8; 32]>,
+}
+
+impl TlsConfig {
+    pub fn build_client(&self) -> anyhow::Result<reqwest::Client> {
+        let mut roots = rustls::RootCertStore::empty();
+        match &self.root_pem {
+            Some(pem) => {
+                for cert in rustls_pemfile::certs(&mut &pem[..])? {
+                    roots.add(&rustls::Certificate(cert))?;
                 }
             }
+            None => roots.add_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.iter().map(|ta| {
+                rustls::OwnedTrustAnchor::from_subject_spki_name_constraints(
+                    ta.subject,
+                    ta.spki,
+                    ta.name_constraints,
+                )
+            })),
         }
-        Err(e) => {
-            // Erro na requisição HTTP
-            Ok(HttpResponse::BadGateway().json(serde_json::json!({
-                "erro": "Falha ao conectar com API externa",
-                "detalhes": e.to_string()
-            })))
+
+        let verifier = Arc::new(PinningVerifier {
+            roots,
+            pins: self.pinned_spki_sha256.clone(),
+        });
+
+        let config = rustls::ClientConfig::builder()
+            .with_safe_defaults()
+            .with_custom_certificate_verifier(verifier);
+
+        let config = match &self.client_identity_pem {
+            Some((cert_pem, key_pem)) => {
+                let certs = rustls_pemfile::certs(&mut &cert_pem[..])?
+                    .into_iter()
+                    .map(rustls::Certificate)
+                    .collect();
+                let key = rustls_pemfile::pkcs8_private_keys(&mut &key_pem[..])?
+                    .into_iter()
+                    .next()
+                    .map(rustls::PrivateKey)
+                    .ok_or_else(|| anyhow::anyhow!("nenhuma chave privada PKCS8 encontrada"))?;
+                config.with_client_auth_cert(certs, key)?
+            }
+            None => config.with_no_client_auth(),
+        };
+
+        let mut builder = reqwest::Client::builder().use_preconfigured_tls(config);
+        if let Some((host, addr)) = &self.connect_override {
+            builder = builder.resolve(host, *addr);
         }
+
+        Ok(builder.build()?)
     }
 }
 
+type SharedHttpClient = web::Data<reqwest::Client>;
+
+// ============================================
+// MIDDLEWARE: CABEÇALHOS DE SEGURANÇA
+// ============================================
+
+// Conteúdo configurável dos cabeçalhos de hardening, injetado via
+// `app_data` em vez de hardcoded no middleware - assim o CSP e a
+// Permissions-Policy variam por ambiente sem recompilar.
+#[derive(Clone)]
+pub struct SecurityHeadersConfig {
+    pub content_security_policy: String,
+    pub referrer_policy: String,
+    pub permissions_policy: String,
+}
+
+impl Default for SecurityHeadersConfig {
+    fn default() -> Self {
+        Self {
+            content_security_policy: "default-src 'self'".to_string(),
+            referrer_policy: "no-referrer".to_string(),
+            permissions_policy: "geolocation=(), microphone=(), camera=()".to_string(),
+        }
+    }
+}
+
+// Um handshake de WebSocket upgradado não pode carregar `X-Frame-Options`
+// nem `Permissions-Policy` sem quebrar o proxy reverso - ambos são
+// cabeçalhos de documento HTML, sem sentido numa conexão já promovida.
+fn is_websocket_upgrade(headers: &actix_web::http::header::HeaderMap) -> bool {
+    let header_contains = |name: &str, needle: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.to_ascii_lowercase().contains(needle))
+            .unwrap_or(false)
+    };
+
+    header_contains("connection", "upgrade") && header_contains("upgrade", "websocket")
+}
+
+pub struct SecurityHeaders;
+
+impl<S, B> actix_web::dev::Transform<S, actix_web::dev::ServiceRequest> for SecurityHeaders
+where
+    S: actix_web::dev::Service<actix_web::dev::ServiceRequest, Response = actix_web::dev::ServiceResponse<B>, Error = actix_web::Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = actix_web::dev::ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type InitError = ();
+    type Transform = SecurityHeadersMiddleware<S>;
+    type Future = std::future::Ready<std::result::Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        std::future::ready(Ok(SecurityHeadersMiddleware { service }))
+    }
+}
+
+pub struct SecurityHeadersMiddleware<S> {
+    service: S,
+}
+
+impl<S, B> actix_web::dev::Service<actix_web::dev::ServiceRequest> for SecurityHeadersMiddleware<S>
+where
+    S: actix_web::dev::Service<actix_web::dev::ServiceRequest, Response = actix_web::dev::ServiceResponse<B>, Error = actix_web::Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = actix_web::dev::ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Future = futures_util::future::LocalBoxFuture<'static, std::result::Result<Self::Response, Self::Error>>;
+
+    actix_web::dev::forward_ready!(service);
+
+    fn call(&self, req: actix_web::dev::ServiceRequest) -> Self::Future {
+        let is_websocket = is_websocket_upgrade(req.headers());
+        let config = req
+            .app_data::<web::Data<SecurityHeadersConfig>>()
+            .map(|data| data.get_ref().clone())
+            .unwrap_or_default();
+        let fut = self.service.call(req);
+
+        Box::pin(async move {
+            let mut res = fut.await?;
+            let headers = res.headers_mut();
+
+            headers.insert(
+                HeaderName::from_static("x-content-type-options"),
+                HeaderValue::from_static("nosniff"),
+            );
+            if let Ok(value) = HeaderValue::from_str(&config.content_security_policy) {
+                headers.insert(HeaderName::from_static("content-security-policy"), value);
+            }
+            if let Ok(value) = HeaderValue::from_str(&config.referrer_policy) {
+                headers.insert(HeaderName::from_static("referrer-policy"), value);
+            }
+
+            if !is_websocket {
+                headers.insert(
+                    HeaderName::from_static("x-frame-options"),
+                    HeaderValue::from_static("DENY"),
+                );
+                if let Ok(value) = HeaderValue::from_str(&config.permissions_policy) {
+                    headers.insert(HeaderName::from_static("permissions-policy"), value);
+                }
+            }
+
+            Ok(res)
+        })
+    }
+}
+
+// GET que chama API externa (proxy) - Busca posts e processa os dados
+async fn buscar_posts_externos(
+    req: HttpRequest,
+    client: SharedHttpClient,
+) -> std::result::Result<HttpResponse, AppError> {
+    let posts: Vec<Post> = client
+        .get("https://jsonplaceholder.typicode.com/posts")
+        .send()
+        .await?
+        .json()
+        .await?;
+
+    // Processa os dados: pega apenas os 5 primeiros posts e cria um
+    // resumo de cada um
+    let posts_processados: Vec<PostProcessado> = posts
+        .into_iter()
+        .take(5) // Limita a 5 posts
+        .map(|post| {
+            // Cria um resumo com os primeiros 50 caracteres
+            let resumo = if post.body.len() > 50 {
+                format!("{}...", &post.body[..50])
+            } else {
+                post.body.clone()
+            };
+
+            // Retorna estrutura processada
+            PostProcessado {
+                id: post.id,
+                titulo: post.title,
+                resumo,
+                tamanho_original: post.body.len(),
+            }
+        })
+        .collect();
+
+    // Retorna os dados processados, marcando a origem externa
+    let resposta = PostsResponse {
+        total_processado: posts_processados.len(),
+        posts: posts_processados,
+    };
+    Ok(resposta
+        .customize()
+        .with_status(StatusCode::ACCEPTED)
+        .insert_header(("X-Source", "jsonplaceholder"))
+        .respond_to(&req))
+}
+
 // ============================================
 // FUNÇÃO MAIN - Configura e inicia o servidor
 // ============================================
@@ -192,24 +1059,56 @@ async fn buscar_posts_externos() -> Result<HttpResponse> {
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     println!("🚀 Servidor iniciando em http://127.0.0.1:8080");
-    
+
+    // Gerado uma vez e compartilhado entre os workers, para que todos
+    // assinem e verifiquem com o mesmo conjunto de chaves.
+    let key_ring = web::Data::new(Mutex::new(KeyRing::generate()));
+
+    // Backend de revogação plugável: em memória por padrão, mas basta trocar
+    // por `RedisTokenStore::connect(...)` para compartilhar o estado entre
+    // múltiplas instâncias do servidor.
+    let token_store: SharedTokenStore = web::Data::from(Arc::new(InMemoryTokenStore::default()) as Arc<dyn TokenStore>);
+
+    // Cliente HTTP único, com pool de conexões, compartilhado por todo
+    // handler que fala com serviços externos - em vez de cada um criar o
+    // seu. `TlsConfig::default()` usa as raízes públicas do webpki sem
+    // pinning nem mTLS; troque os campos para falar com um upstream que
+    // exija certificado cliente ou uma CA privada.
+    let http_client = web::Data::new(
+        TlsConfig::default()
+            .build_client()
+            .expect("cliente HTTP com TLS configurado"),
+    );
+
+    let security_headers = web::Data::new(SecurityHeadersConfig::default());
+
     // Cria e configura o servidor HTTP
-    HttpServer::new(|| {
+    HttpServer::new(move || {
         App::new()
+            .app_data(key_ring.clone())
+            .app_data(token_store.clone())
+            .app_data(http_client.clone())
+            .app_data(security_headers.clone())
+            .wrap(SecurityHeaders)
             // Rotas GET
             .route("/", web::get().to(index))
             .route("/usuarios/{id}", web::get().to(get_usuario))
             .route("/posts-externos", web::get().to(buscar_posts_externos))
-            
+            .route("/.well-known/jwks.json", web::get().to(jwks))
+
             // Rota POST
             .route("/usuarios", web::post().to(criar_usuario))
-            
+            .route("/login", web::post().to(login))
+            .route("/refresh", web::post().to(refresh_token))
+            .route("/logout", web::post().to(logout))
+            .route("/admin/revoke/{usuario}", web::post().to(revogar_todos))
+
             // Rota PUT
             .route("/usuarios/{id}", web::put().to(atualizar_usuario))
-            
+
             // Rota DELETE
             .route("/usuarios/{id}", web::delete().to(deletar_usuario))
-            
+
             // Rota PATCH
             .route("/usuarios/{id}", web::patch().to(atualizar_parcial))
     })
@@ -12,6 +12,7 @@ use tokio::{
 
 use async_trait::async_trait;
 use serde::Deserialize;
+use sha2::Digest;
 use thiserror::Error;
 
 /* -------------------------------------------------
@@ -27,6 +28,21 @@ pub enum AppError {
     InvalidInput(String),
 }
 
+impl AppError {
+    /// Transient failures (connect/timeout/5xx) are worth a retry; anything
+    /// else (4xx, bad input) is the caller's fault and retrying won't help.
+    fn is_retryable(&self) -> bool {
+        match self {
+            AppError::Http(err) => {
+                err.is_timeout()
+                    || err.is_connect()
+                    || err.status().map(|s| s.is_server_error()).unwrap_or(false)
+            }
+            AppError::InvalidInput(_) => false,
+        }
+    }
+}
+
 type AppResult<T> = Result<T, AppError>;
 
 /* -------------------------------------------------
@@ -144,6 +160,198 @@ impl<'a> Worker for HttpWorker<'a> {
     }
 }
 
+/* -------------------------------------------------
+ * RETRY POLICY: capped exponential backoff with full jitter
+ * ------------------------------------------------- */
+
+/// Tunable enough to unit-test with a fixed `rng_seed` and a fake clock.
+#[derive(Debug, Clone, Copy)]
+struct RetryConfig {
+    base: Duration,
+    max_delay: Duration,
+    max_retries: u32,
+    rng_seed: Option<u64>,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            base: Duration::from_millis(100),
+            max_delay: Duration::from_secs(30),
+            max_retries: 5,
+            rng_seed: None,
+        }
+    }
+}
+
+impl RetryConfig {
+    /// "Full jitter": sleep a uniform random amount in `[0, base * 2^attempt]`,
+    /// clamped to `max_delay`, so concurrent workers retrying together don't
+    /// all wake up and stampede the upstream at the same instant.
+    fn delay_for(&self, attempt: u32, rng: &mut impl rand::Rng) -> Duration {
+        let capped = self
+            .base
+            .saturating_mul(1u32.checked_shl(attempt).unwrap_or(u32::MAX))
+            .min(self.max_delay);
+        Duration::from_secs_f64(rng.gen_range(0.0..=capped.as_secs_f64().max(f64::EPSILON)))
+    }
+}
+
+/// Wraps any `Worker` and re-attempts `do_work` on transient failures,
+/// reclassifying 4xx responses as `AppError::InvalidInput` so they fail fast.
+struct RetryingWorker {
+    inner: Arc<dyn Worker>,
+    config: RetryConfig,
+}
+
+impl RetryingWorker {
+    fn new(inner: Arc<dyn Worker>, config: RetryConfig) -> Self {
+        Self { inner, config }
+    }
+
+    fn classify(err: AppError) -> AppError {
+        if let AppError::Http(ref e) = err {
+            if let Some(status) = e.status() {
+                if status.is_client_error() {
+                    return AppError::InvalidInput(format!("{status}"));
+                }
+            }
+        }
+        err
+    }
+}
+
+#[async_trait]
+impl Worker for RetryingWorker {
+    async fn do_work(&self, id: u64) -> AppResult<()> {
+        let mut rng: rand::rngs::StdRng = match self.config.rng_seed {
+            Some(seed) => rand::SeedableRng::seed_from_u64(seed),
+            None => rand::SeedableRng::from_entropy(),
+        };
+
+        let mut attempt = 0;
+        loop {
+            match self.inner.do_work(id).await {
+                Ok(()) => return Ok(()),
+                Err(err) => {
+                    let err = Self::classify(err);
+                    if !err.is_retryable() || attempt >= self.config.max_retries {
+                        return Err(err);
+                    }
+
+                    let delay = self.config.delay_for(attempt, &mut rng);
+                    attempt += 1;
+                    sleep(delay).await;
+                }
+            }
+        }
+    }
+}
+
+/* -------------------------------------------------
+ * TLS CONFIG FOR THE UPSTREAM CLIENT
+ *
+ * Builds the single `reqwest::Client` shared by every `HttpWorker`, on a
+ * rustls backend so we control the trust roots, can pin the upstream's
+ * SPKI, and can present a client certificate for mTLS instead of relying
+ * on whatever the platform's default TLS stack happens to trust.
+ * ------------------------------------------------- */
+
+struct PinningVerifier {
+    roots: rustls::RootCertStore,
+    pins: Vec<[u8; 32]>,
+}
+
+impl rustls::client::ServerCertVerifier for PinningVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &rustls::Certificate,
+        intermediates: &[rustls::Certificate],
+        server_name: &rustls::ServerName,
+        scts: &mut dyn Iterator<Item = &[u8]>,
+        ocsp_response: &[u8],
+        now: std::time::SystemTime,
+    ) -> Result<rustls::client::ServerCertVerified, rustls::Error> {
+        let webpki = rustls::client::WebPkiVerifier::new(self.roots.clone(), None);
+        webpki.verify_server_cert(end_entity, intermediates, server_name, scts, ocsp_response, now)?;
+
+        if self.pins.is_empty() {
+            return Ok(rustls::client::ServerCertVerified::assertion());
+        }
+
+        let (_, parsed) = x509_parser::parse_x509_certificate(&end_entity.0)
+            .map_err(|e| rustls::Error::General(format!("invalid certificate: {e}")))?;
+        let hash: [u8; 32] = sha2::Sha256::digest(parsed.tbs_certificate.subject_pki.raw).into();
+
+        if self.pins.contains(&hash) {
+            Ok(rustls::client::ServerCertVerified::assertion())
+        } else {
+            Err(rustls::Error::General(
+                "certificate SPKI not in the pinned allow-list".to_string(),
+            ))
+        }
+    }
+}
+
+/// Trust roots, optional client identity (mTLS) and optional SPKI pins for
+/// the upstream client. `Default` trusts the bundled webpki roots with no
+/// client cert and no pinning — a drop-in replacement for `Client::new()`.
+#[derive(Default, Clone)]
+struct TlsConfig {
+    root_pem: Option<Vec<u8>>,
+    client_identity_pem: Option<(Vec<u8>, Vec<u8>)>,
+    pinned_spki_sha256: Vec<[u8; 32]>,
+}
+
+impl TlsConfig {
+    fn build_client(&self) -> anyhow::Result<reqwest::Client> {
+        let mut roots = rustls::RootCertStore::empty();
+        match &self.root_pem {
+            Some(pem) => {
+                for cert in rustls_pemfile::certs(&mut &pem[..])? {
+                    roots.add(&rustls::Certificate(cert))?;
+                }
+            }
+            None => roots.add_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.iter().map(|ta| {
+                rustls::OwnedTrustAnchor::from_subject_spki_name_constraints(
+                    ta.subject,
+                    ta.spki,
+                    ta.name_constraints,
+                )
+            })),
+        }
+
+        let verifier = Arc::new(PinningVerifier {
+            roots,
+            pins: self.pinned_spki_sha256.clone(),
+        });
+
+        let config = rustls::ClientConfig::builder()
+            .with_safe_defaults()
+            .with_custom_certificate_verifier(verifier);
+
+        let config = match &self.client_identity_pem {
+            Some((cert_pem, key_pem)) => {
+                let certs = rustls_pemfile::certs(&mut &cert_pem[..])?
+                    .into_iter()
+                    .map(rustls::Certificate)
+                    .collect();
+                let key = rustls_pemfile::pkcs8_private_keys(&mut &key_pem[..])?
+                    .into_iter()
+                    .next()
+                    .map(rustls::PrivateKey)
+                    .ok_or_else(|| anyhow::anyhow!("no PKCS8 private key found"))?;
+                config.with_client_auth_cert(certs, key)?
+            }
+            None => config.with_no_client_auth(),
+        };
+
+        Ok(reqwest::Client::builder()
+            .use_preconfigured_tls(config)
+            .build()?)
+    }
+}
+
 /* -------------------------------------------------
  * FUTURE + PIN
  * ------------------------------------------------- */
@@ -194,12 +402,17 @@ async fn main() -> AppResult<()> {
         user_agent,
     };
 
-    let client = reqwest::Client::new();
+    // A single rustls-backed, connection-pooling client, shared by every
+    // worker instead of each one standing up its own `Client::new()`.
+    let client = TlsConfig::default()
+        .build_client()
+        .expect("TLS-configured HTTP client");
 
     // Worker borrows `config` via lifetime `'a`
     let worker: Arc<dyn Worker> = Arc::new(
         HttpWorker::new(client, config, stats.clone())
     );
+    let worker: Arc<dyn Worker> = Arc::new(RetryingWorker::new(worker, RetryConfig::default()));
 
     run_workers(worker).await?;
 
@@ -410,4 +410,94 @@ println!("{:*^10}", "hi");      // "****hi****"
 // Combining formatting
 let value = 1234.5678;
 println!("{:>15.2}", value);    // "        1234.57"
+
+
+
+//// std::fs: symlink-aware metadata walking
+/// `metadata()` follows symlinks, so calling it on a symlink reports the
+/// *target's* file type and silently hides that the entry is a link at
+/// all. `symlink_metadata()` doesn't follow the link - it's the one to use
+/// whenever "is this actually a symlink" matters, e.g. before recursing
+/// into it (following one into a cycle would otherwise loop forever).
+/// `is_file()`, `is_dir()`, and `is_symlink()` are mutually exclusive on
+/// whichever `FileType` you end up with.
+use std::fs;
+use std::path::Path;
+
+fn fs_walk_example(root: &Path) -> std::io::Result<()> {
+    walk(root, 0)
+}
+
+fn walk(dir: &Path, depth: usize) -> std::io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        // Doesn't follow the link: a symlink is reported as a symlink,
+        // never as whatever it happens to point at.
+        let file_type = fs::symlink_metadata(&path)?.file_type();
+        let indent = "  ".repeat(depth);
+
+        if file_type.is_symlink() {
+            // Contrast: `metadata` follows the link, so on this same path
+            // it reports the *target's* type instead of "this is a link".
+            let target = match fs::metadata(&path) {
+                Ok(meta) if meta.is_dir() => "dir",
+                Ok(meta) if meta.is_file() => "file",
+                Ok(_) => "other",
+                Err(_) => "broken link",
+            };
+            println!(
+                "{indent}{} -> symlink (metadata() would instead report: {target})",
+                path.display()
+            );
+        } else if file_type.is_dir() {
+            println!("{indent}{} -> dir", path.display());
+            walk(&path, depth + 1)?;
+        } else {
+            debug_assert!(file_type.is_file());
+            println!("{indent}{} -> file", path.display());
+        }
+    }
+    Ok(())
+}
+
+// Requires in Cargo.toml: tempfile = "3"
+#[cfg(test)]
+#[cfg(unix)]
+mod fs_walk_tests {
+    use super::*;
+    use std::os::unix::fs::symlink;
+    use tempfile::tempdir;
+
+    #[test]
+    fn symlink_metadata_reports_the_link_not_the_target() {
+        let dir = tempdir().unwrap();
+        let real_path = dir.path().join("real.txt");
+        fs::write(&real_path, b"hi").unwrap();
+
+        let link_path = dir.path().join("link.txt");
+        symlink(&real_path, &link_path).unwrap();
+
+        let link_type = fs::symlink_metadata(&link_path).unwrap().file_type();
+        let predicates = [link_type.is_file(), link_type.is_dir(), link_type.is_symlink()];
+        assert_eq!(predicates, [false, false, true]);
+        assert_eq!(predicates.iter().filter(|is_true| **is_true).count(), 1);
+
+        // Same path, but `metadata` follows the link: it reports the
+        // *target's* type, not "this is a symlink".
+        let followed_type = fs::metadata(&link_path).unwrap().file_type();
+        assert!(followed_type.is_file());
+        assert!(!followed_type.is_symlink());
+    }
+
+    #[test]
+    fn walk_visits_files_dirs_and_symlinks_without_error() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("a.txt"), b"a").unwrap();
+        fs::create_dir(dir.path().join("sub")).unwrap();
+        fs::write(dir.path().join("sub/b.txt"), b"b").unwrap();
+        symlink(dir.path().join("a.txt"), dir.path().join("link_to_a")).unwrap();
+
+        assert!(fs_walk_example(dir.path()).is_ok());
+    }
+}
 println!("{:<15.2}", value);    // "1234.57        "
\ No newline at end of file